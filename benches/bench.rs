@@ -40,6 +40,20 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             format!("{qs}")
         })
     });
+
+    // Rendering plain integer values, which take the fast path that skips percent encoding,
+    // compared against the same values with a leading `+` that forces the generic path
+    let mut fast = QueryString::dynamic();
+    let mut encoded = QueryString::dynamic();
+    for i in 0..20 {
+        fast.push(format!("id{i}"), i * 1000);
+        encoded.push(format!("id{i}"), format!("+{}", i * 1000));
+    }
+
+    let mut group = c.benchmark_group("render integers");
+    group.bench_function("fast path", |b| b.iter(|| format!("{fast}")));
+    group.bench_function("percent encoded", |b| b.iter(|| format!("{encoded}")));
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
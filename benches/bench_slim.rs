@@ -1,4 +1,5 @@
 use criterion::{criterion_group, criterion_main, Criterion};
+use std::fmt::Write;
 
 use query_string_builder::QueryString;
 
@@ -25,6 +26,33 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             format!("{qs}")
         })
     });
+
+    // Rendering into a reused buffer, which does not allocate at all
+    // since keys and values are encoded directly from their `Display` output.
+    let qs = QueryString::simple()
+        .with_value("q", "apple???")
+        .with_value("category", "fruits and vegetables")
+        .with_value("weight", 99.9)
+        .with_value("tasty", true);
+    let mut buffer = String::with_capacity(256);
+    c.bench_function("render into buffer (slim)", |b| {
+        b.iter(|| {
+            buffer.clear();
+            write!(buffer, "{qs}").unwrap();
+            buffer.len()
+        })
+    });
+
+    // Rendering plain integer values, which take the fast path that skips percent encoding
+    let ids: Vec<(String, u32)> = (0..20).map(|i| (format!("id{i}"), i * 1000)).collect();
+    let qs = QueryString::simple().with_slice(&ids);
+    c.bench_function("render integers (slim)", |b| {
+        b.iter(|| {
+            buffer.clear();
+            write!(buffer, "{qs}").unwrap();
+            buffer.len()
+        })
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
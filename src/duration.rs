@@ -0,0 +1,85 @@
+use std::fmt::{Display, Formatter, Write};
+use std::time::Duration;
+
+/// The representation of a [`Duration`] used by
+/// [`QueryString::with_duration`](crate::QueryString::with_duration).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DurationUnit {
+    /// Whole seconds, e.g. `30`. Fractional seconds are truncated.
+    Seconds,
+    /// Whole milliseconds, e.g. `30000`. Fractional milliseconds are truncated.
+    Millis,
+    /// An ISO 8601 duration, e.g. `PT30S` or `PT1H2M3.5S`.
+    Iso8601,
+}
+
+/// A helper to render a [`Duration`] in the specified [`DurationUnit`].
+pub(crate) struct DisplayDuration(pub Duration, pub DurationUnit);
+
+impl Display for DisplayDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let DisplayDuration(duration, unit) = self;
+        match unit {
+            DurationUnit::Seconds => write!(f, "{}", duration.as_secs()),
+            DurationUnit::Millis => write!(f, "{}", duration.as_millis()),
+            DurationUnit::Iso8601 => {
+                let total = duration.as_secs();
+                let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+                let nanos = duration.subsec_nanos();
+
+                f.write_str("PT")?;
+                if hours > 0 {
+                    write!(f, "{hours}H")?;
+                }
+                if minutes > 0 {
+                    write!(f, "{minutes}M")?;
+                }
+                if seconds > 0 || nanos > 0 || total == 0 {
+                    write!(f, "{seconds}")?;
+                    if nanos > 0 {
+                        let fraction = format!("{nanos:09}");
+                        write!(f, ".{}", fraction.trim_end_matches('0'))?;
+                    }
+                    f.write_char('S')?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(duration: Duration, unit: DurationUnit) -> String {
+        DisplayDuration(duration, unit).to_string()
+    }
+
+    #[test]
+    fn test_seconds_and_millis() {
+        let duration = Duration::from_millis(30_999);
+        assert_eq!(format(duration, DurationUnit::Seconds), "30");
+        assert_eq!(format(duration, DurationUnit::Millis), "30999");
+        assert_eq!(format(Duration::ZERO, DurationUnit::Seconds), "0");
+    }
+
+    #[test]
+    fn test_iso8601() {
+        let tests = [
+            (Duration::ZERO, "PT0S"),
+            (Duration::from_secs(30), "PT30S"),
+            (Duration::from_secs(60), "PT1M"),
+            (Duration::from_secs(3600), "PT1H"),
+            (Duration::from_secs(3723), "PT1H2M3S"),
+            (Duration::from_millis(3_500), "PT3.5S"),
+            (Duration::from_nanos(1), "PT0.000000001S"),
+            (Duration::from_secs(90_000), "PT25H"),
+            (Duration::from_millis(7_200_250), "PT2H0.25S"),
+        ];
+
+        for (duration, expected) in tests {
+            assert_eq!(format(duration, DurationUnit::Iso8601), expected);
+        }
+    }
+}
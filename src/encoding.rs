@@ -0,0 +1,426 @@
+use crate::QUERY;
+use percent_encoding::{
+    percent_decode_str, percent_encode_byte, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC,
+};
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// https://url.spec.whatwg.org/#application-x-www-form-urlencoded-percent-encode-set
+///
+/// Everything except alphanumeric characters and `*`, `-`, `.` and `_` is encoded.
+/// Spaces are additionally rendered as `+` by the [`Encoder`].
+pub(crate) const FORM: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'*')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_');
+
+/// https://www.rfc-editor.org/rfc/rfc3986#section-2.3
+///
+/// Everything except unreserved characters is encoded, as required e.g. by AWS Signature Version 4.
+pub(crate) const UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// https://www.rfc-editor.org/rfc/rfc3986#section-3.4
+///
+/// Everything except unreserved characters, sub-delimiters and `:`, `@`, `/` and `?`
+/// is encoded. Since they delimit key-value pairs, `&` and `=` are encoded nonetheless.
+pub(crate) const RFC3986_QUERY: &AsciiSet = &NON_ALPHANUMERIC
+    // unreserved
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~')
+    // sub-delims, except for `&` and `=`
+    .remove(b'!')
+    .remove(b'$')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')')
+    .remove(b'*')
+    .remove(b'+')
+    .remove(b',')
+    .remove(b';')
+    // pchar and query
+    .remove(b':')
+    .remove(b'@')
+    .remove(b'/')
+    .remove(b'?');
+
+/// Determines whether the byte is never percent encoded by any of the sets above,
+/// i.e. an ASCII alphanumeric character or one of `-`, `.` and `_`.
+pub(crate) fn is_never_encoded(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_')
+}
+
+/// The percent encoding scheme used when rendering a [`QueryString`](crate::QueryString).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// Encodes the WHATWG query percent-encode set, as well as `%`, `&`, `=` and `+`
+    /// to allow for unambiguous decoding of nested values.
+    #[default]
+    Query,
+    /// Encodes only what RFC 3986 requires for a query component, keeping e.g. `+` literal.
+    /// The pair delimiters `&` and `=` are still encoded within keys and values.
+    Rfc3986,
+    /// Encodes like `application/x-www-form-urlencoded` HTML forms: spaces are rendered
+    /// as `+` and everything except alphanumeric characters and `*`, `-`, `.` and `_`
+    /// is percent encoded. This matches the output of `serde_urlencoded` and
+    /// `form_urlencoded`; notably, `~` is encoded as `%7E` while `*` is kept literal.
+    Form,
+}
+
+impl Encoding {
+    /// Gets the set of ASCII characters to percent encode.
+    pub(crate) fn ascii_set(&self) -> &'static AsciiSet {
+        match self {
+            Encoding::Query => QUERY,
+            Encoding::Rfc3986 => RFC3986_QUERY,
+            Encoding::Form => FORM,
+        }
+    }
+}
+
+/// A custom strategy for encoding keys and values, see [`QueryString::with_encoder`](crate::QueryString::with_encoder).
+///
+/// The encoder is fully responsible for the output, i.e. it must encode the pair
+/// delimiters `&` and `=` itself to keep the query string unambiguous.
+///
+/// [`Encoding`] implements this trait, so the built-in schemes can be used as a base
+/// for custom encoders.
+///
+/// ## Example
+///
+/// ```
+/// use std::borrow::Cow;
+/// use query_string_builder::{Encoding, QueryString, ValueEncoder};
+///
+/// struct Uppercase;
+///
+/// impl ValueEncoder for Uppercase {
+///     fn encode<'a>(&self, s: &'a str) -> Cow<'a, str> {
+///         Cow::Owned(Encoding::Query.encode(s).to_uppercase())
+///     }
+/// }
+///
+/// let qs = QueryString::dynamic()
+///             .with_encoder(Box::new(Uppercase))
+///             .with_value("q", "apple pie");
+///
+/// assert_eq!(qs.to_string(), "?Q=APPLE%20PIE");
+/// ```
+pub trait ValueEncoder: Send + Sync {
+    /// Encodes a single key or value.
+    fn encode<'a>(&self, s: &'a str) -> Cow<'a, str>;
+}
+
+impl ValueEncoder for Encoding {
+    fn encode<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        let mut encoded = String::new();
+        Encoder::new(*self)
+            .write(&mut encoded, s)
+            .expect("writing to a String never fails");
+        if encoded == s {
+            Cow::Borrowed(s)
+        } else {
+            Cow::Owned(encoded)
+        }
+    }
+}
+
+/// The effective encoding applied to keys and values when rendering.
+pub(crate) struct Encoder<'a> {
+    /// The set of ASCII characters to percent encode.
+    pub set: &'static AsciiSet,
+    /// ASCII characters to encode regardless of whether they are part of the set.
+    pub extra: Vec<u8>,
+    /// Characters to write literally regardless of all other rules.
+    pub allowed: &'a [char],
+    /// Whether spaces are rendered as `+` rather than `%20`.
+    pub space_as_plus: bool,
+    /// A custom encoder replacing all other rules.
+    pub custom: Option<&'a dyn ValueEncoder>,
+    /// Whether percent escapes use lowercase hexadecimal digits.
+    pub lowercase: bool,
+}
+
+impl Encoder<'_> {
+    pub fn new(encoding: Encoding) -> Self {
+        Self {
+            set: encoding.ascii_set(),
+            extra: Vec::new(),
+            allowed: &[],
+            space_as_plus: encoding == Encoding::Form,
+            custom: None,
+            lowercase: false,
+        }
+    }
+
+    /// Percent encodes the value and writes it to the writer.
+    pub fn write<W: Write + ?Sized>(&self, w: &mut W, value: &str) -> std::fmt::Result {
+        if let Some(custom) = self.custom {
+            return w.write_str(&custom.encode(value));
+        }
+
+        // Fast path for values such as integers that do not require any encoding.
+        if value
+            .bytes()
+            .all(|byte| is_never_encoded(byte) && !self.extra.contains(&byte))
+        {
+            return w.write_str(value);
+        }
+
+        if self.allowed.is_empty() {
+            return self.write_segment(w, value);
+        }
+
+        let mut start = 0;
+        for (i, c) in value.char_indices() {
+            if self.allowed.contains(&c) {
+                self.write_segment(w, &value[start..i])?;
+                w.write_char(c)?;
+                start = i + c.len_utf8();
+            }
+        }
+        self.write_segment(w, &value[start..])
+    }
+
+    /// Percent encodes arbitrary bytes and writes them to the writer.
+    /// Bytes that are not part of valid UTF-8 sequences are always percent encoded.
+    pub fn write_bytes<W: Write + ?Sized>(&self, w: &mut W, value: &[u8]) -> std::fmt::Result {
+        let mut rest = value;
+        while !rest.is_empty() {
+            let (valid, invalid) = match std::str::from_utf8(rest) {
+                Ok(valid) => (valid, &[][..]),
+                Err(e) => {
+                    let (valid, tail) = rest.split_at(e.valid_up_to());
+                    let invalid_len = e.error_len().unwrap_or(tail.len());
+                    let valid = std::str::from_utf8(valid).expect("prefix is valid UTF-8");
+                    (valid, &tail[..invalid_len])
+                }
+            };
+
+            self.write(w, valid)?;
+            for &byte in invalid {
+                self.write_escape(w, percent_encode_byte(byte))?;
+            }
+            rest = &rest[valid.len() + invalid.len()..];
+        }
+        Ok(())
+    }
+
+    /// Writes a percent escape such as `%C3`, using lowercase digits if requested.
+    fn write_escape<W: Write + ?Sized>(&self, w: &mut W, escape: &str) -> std::fmt::Result {
+        if !self.lowercase {
+            return w.write_str(escape);
+        }
+
+        for c in escape.chars() {
+            w.write_char(c.to_ascii_lowercase())?;
+        }
+        Ok(())
+    }
+
+    /// Percent encodes a segment not containing any allowed characters.
+    fn write_segment<W: Write + ?Sized>(&self, w: &mut W, value: &str) -> std::fmt::Result {
+        for chunk in utf8_percent_encode(value, self.set) {
+            // All sets encode the percent sign, so a chunk starting with it is an encoded byte.
+            if chunk.starts_with('%') {
+                if self.space_as_plus && chunk == "%20" {
+                    w.write_char('+')?;
+                } else {
+                    self.write_escape(w, chunk)?;
+                }
+                continue;
+            }
+
+            if self.extra.is_empty() {
+                w.write_str(chunk)?;
+                continue;
+            }
+
+            let mut start = 0;
+            for (i, byte) in chunk.bytes().enumerate() {
+                if self.extra.contains(&byte) {
+                    w.write_str(&chunk[start..i])?;
+                    self.write_escape(w, percent_encode_byte(byte))?;
+                    start = i + 1;
+                }
+            }
+            w.write_str(&chunk[start..])?;
+        }
+        Ok(())
+    }
+}
+
+/// Percent encodes a key or value exactly like the builder does when rendering
+/// using the default [`Encoding::Query`].
+///
+/// ## Example
+///
+/// ```
+/// use query_string_builder::encode_value;
+///
+/// assert_eq!(encode_value("🍎 a+b"), "%F0%9F%8D%8E%20a%2Bb");
+/// assert_eq!(encode_value("apple"), "apple");
+/// ```
+pub fn encode_value(s: &str) -> Cow<'_, str> {
+    utf8_percent_encode(s, QUERY).into()
+}
+
+/// Decodes a percent encoded key or value.
+///
+/// Invalid UTF-8 sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+///
+/// ## Example
+///
+/// ```
+/// use query_string_builder::decode_value;
+///
+/// assert_eq!(decode_value("%F0%9F%8D%8E%20a%2Bb"), "🍎 a+b");
+/// assert_eq!(decode_value("apple"), "apple");
+/// ```
+pub fn decode_value(s: &str) -> Cow<'_, str> {
+    percent_decode_str(s).decode_utf8_lossy()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_round_trip() {
+        let values = ["", "apple", "🍎 apple", "a&b=c", "100%", "1+1", "?#[]"];
+        for value in values {
+            assert_eq!(decode_value(&encode_value(value)), value);
+        }
+    }
+
+    #[test]
+    fn test_borrowed() {
+        assert!(matches!(encode_value("apple"), Cow::Borrowed("apple")));
+        assert!(matches!(decode_value("apple"), Cow::Borrowed("apple")));
+    }
+
+    /// Cross-checks the form encoding against `serde_urlencoded`.
+    #[test]
+    fn test_form_matches_serde_urlencoded() {
+        let ascii: String = (0u8..0x80).map(char::from).collect();
+        let values = [
+            "",
+            "apple",
+            "fruits and vegetables",
+            "1+1=2",
+            "a&b",
+            "100%",
+            "~user",
+            "*.-_",
+            "!$'()*,;:@/?",
+            "\"<>[]\\^`{|}#",
+            "Grünkohl",
+            "🍎 apple",
+            "\t\r\n",
+            &ascii,
+        ];
+
+        for key in values {
+            for value in values {
+                let qs = crate::QueryString::form().with_value(key, value);
+                let expected = serde_urlencoded::to_string([(key, value)]).unwrap();
+                assert_eq!(qs.to_string(), format!("?{expected}"));
+            }
+        }
+
+        let qs = crate::QueryString::form()
+            .with_value("q", "🍎 apple")
+            .with_value("page", 2);
+        let expected = serde_urlencoded::to_string([("q", "🍎 apple"), ("page", "2")]).unwrap();
+        assert_eq!(qs.to_string(), format!("?{expected}"));
+    }
+
+    #[test]
+    fn test_value_encoder_for_encoding() {
+        assert!(matches!(
+            Encoding::Query.encode("apple"),
+            Cow::Borrowed("apple")
+        ));
+        assert_eq!(Encoding::Query.encode("a b&c"), encode_value("a b&c"));
+        assert_eq!(Encoding::Rfc3986.encode("a+b c"), "a+b%20c");
+        assert_eq!(Encoding::Form.encode("a+b c"), "a%2Bb+c");
+    }
+
+    #[test]
+    fn test_write_bytes() {
+        let mut encoder = Encoder::new(Encoding::Query);
+        encoder.allowed = &['#'];
+
+        let mut encoded = String::new();
+        encoder
+            .write_bytes(&mut encoded, b"a#b \xF0\x9F\x8D \xF0\x9F\x8D\x8E")
+            .unwrap();
+        assert_eq!(encoded, "a#b%20%F0%9F%8D%20%F0%9F%8D%8E");
+    }
+
+    #[test]
+    fn test_fast_path() {
+        let mut encoder = Encoder::new(Encoding::Query);
+        let mut encoded = String::new();
+        encoder.write(&mut encoded, "Abc-1.2_3").unwrap();
+        assert_eq!(encoded, "Abc-1.2_3");
+
+        encoder.extra.push(b'b');
+        encoded.clear();
+        encoder.write(&mut encoded, "Abc-1.2_3").unwrap();
+        assert_eq!(encoded, "A%62c-1.2_3");
+
+        for encoding in [Encoding::Query, Encoding::Rfc3986, Encoding::Form] {
+            for byte in (0..=0x7F).filter(|&byte| is_never_encoded(byte)) {
+                let c = [byte];
+                let c = std::str::from_utf8(&c).unwrap();
+                assert_eq!(utf8_percent_encode(c, encoding.ascii_set()).to_string(), c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lowercase() {
+        let mut encoder = Encoder::new(Encoding::Form);
+        encoder.lowercase = true;
+        encoder.extra.push(b'A');
+
+        let mut encoded = String::new();
+        encoder.write(&mut encoded, "ABü ~").unwrap();
+        encoder.write_bytes(&mut encoded, &[0xFE]).unwrap();
+        assert_eq!(encoded, "%41B%c3%bc+%7e%fe");
+    }
+
+    #[test]
+    fn test_extra_encoded() {
+        let mut encoder = Encoder::new(Encoding::Form);
+        encoder.extra = vec![b'*', b'a'];
+
+        let mut result = String::new();
+        encoder.write(&mut result, "a* b~").unwrap();
+        assert_eq!(result, "%61%2A+b%7E");
+    }
+
+    #[test]
+    fn test_allowed() {
+        let mut encoder = Encoder::new(Encoding::Query);
+        encoder.extra = vec![b':'];
+        encoder.allowed = &[':', ' ', 'ü'];
+
+        let mut result = String::new();
+        encoder.write(&mut result, "10:30 Grünkohl&").unwrap();
+        assert_eq!(result, "10:30 Grünkohl%26");
+    }
+
+    #[test]
+    fn test_decode_invalid_utf8() {
+        assert_eq!(decode_value("%FFa"), "\u{FFFD}a");
+    }
+}
@@ -0,0 +1,211 @@
+use crate::Kvp;
+
+/// A view into the first pair of a [`QueryString`](crate::QueryString) with a given key,
+/// which may either be occupied or vacant.
+///
+/// This type is created by [`QueryString::entry`](crate::QueryString::entry).
+#[derive(Debug)]
+pub enum Entry<'a> {
+    /// A pair with the key exists.
+    Occupied(OccupiedEntry<'a>),
+    /// No pair with the key exists.
+    Vacant(VacantEntry<'a>),
+}
+
+/// A view into an occupied entry of a [`QueryString`](crate::QueryString).
+#[derive(Debug)]
+pub struct OccupiedEntry<'a> {
+    pub(crate) pair: &'a mut Kvp,
+}
+
+/// A view into a vacant entry of a [`QueryString`](crate::QueryString).
+#[derive(Debug)]
+pub struct VacantEntry<'a> {
+    pub(crate) key: String,
+    pub(crate) pairs: &'a mut Vec<Kvp>,
+}
+
+impl<'a> Entry<'a> {
+    /// Gets the key of this entry.
+    pub fn key(&self) -> &str {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensures a value exists by appending a pair with the default value if the entry is vacant,
+    /// and returns a mutable reference to the value.
+    pub fn or_insert(self, default: String) -> &'a mut String {
+        self.or_insert_with(|| default)
+    }
+
+    /// Ensures a value exists by appending a pair with the result of the default function
+    /// if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> String>(self, default: F) -> &'a mut String {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Modifies the value of an occupied entry before any potential inserts.
+    pub fn and_modify<F: FnOnce(&mut String)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a> OccupiedEntry<'a> {
+    /// Gets the key of this entry.
+    pub fn key(&self) -> &str {
+        &self.pair.key
+    }
+
+    /// Gets the value of this entry.
+    pub fn get(&self) -> &str {
+        self.pair.value()
+    }
+
+    /// Gets a mutable reference to the value of this entry.
+    ///
+    /// If the entry is a bare flag, it is turned into a regular key-value pair.
+    /// Values that were added already percent encoded, e.g. using
+    /// [`with_value_raw`](crate::QueryString::with_value_raw), are decoded first,
+    /// and are percent encoded again when rendering.
+    pub fn get_mut(&mut self) -> &mut String {
+        self.pair.flag = false;
+        self.pair.decoded_value_mut()
+    }
+
+    /// Converts the entry into a mutable reference to its value.
+    ///
+    /// If the entry is a bare flag, it is turned into a regular key-value pair.
+    /// Encoded values are decoded first, like in [`get_mut`](Self::get_mut).
+    pub fn into_mut(self) -> &'a mut String {
+        self.pair.flag = false;
+        self.pair.decoded_value_mut()
+    }
+
+    /// Replaces the value of this entry, returning the previous value.
+    pub fn insert(&mut self, value: String) -> String {
+        std::mem::replace(self.get_mut(), value)
+    }
+}
+
+impl<'a> VacantEntry<'a> {
+    /// Gets the key of this entry.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Appends a pair with the entry's key and the specified value,
+    /// returning a mutable reference to the value.
+    ///
+    /// The pair is appended even if the builder reached the limit set using
+    /// [`with_max_pairs`](crate::QueryString::with_max_pairs).
+    pub fn insert(self, value: String) -> &'a mut String {
+        self.pairs.push(Kvp::new(self.key, value));
+        self.pairs
+            .last_mut()
+            .expect("a pair was just added")
+            .value_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Entry, QueryString};
+
+    #[test]
+    fn test_entry() {
+        let mut qs = QueryString::dynamic()
+            .with_value("page", 1)
+            .with_value("page", 5);
+
+        let page = qs
+            .entry("page")
+            .and_modify(|v| *v = "2".to_string())
+            .or_insert("1".into());
+        assert_eq!(page, "2");
+
+        qs.entry("size")
+            .and_modify(|_| unreachable!())
+            .or_insert("20".into())
+            .push('0');
+
+        assert_eq!(qs.to_string(), "?page=2&page=5&size=200");
+    }
+
+    #[test]
+    fn test_entry_variants() {
+        let mut qs = QueryString::dynamic()
+            .with_value("q", "apple")
+            .with_flag("verbose");
+
+        match qs.entry("q") {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.key(), "q");
+                assert_eq!(entry.get(), "apple");
+                assert_eq!(entry.insert("pear".into()), "apple");
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+
+        match qs.entry("page".to_string()) {
+            Entry::Vacant(entry) => {
+                assert_eq!(entry.key(), "page");
+                entry.insert("1".into());
+            }
+            Entry::Occupied(_) => unreachable!(),
+        }
+
+        // Modifying a flag turns it into a regular pair.
+        assert_eq!(qs.entry("verbose").key(), "verbose");
+        *qs.entry("verbose").or_insert_with(|| unreachable!()) = "1".into();
+
+        assert_eq!(qs.to_string(), "?q=pear&verbose=1&page=1");
+    }
+
+    #[test]
+    fn test_entry_encoded_values() {
+        let mut qs = QueryString::dynamic()
+            .with_value_raw("a%20b", "x%20y")
+            .with_value_preencoded("c", "%26")
+            .with_bytes("d", b"e f")
+            .with_raw_fragment_verbatim("g%26h");
+
+        *qs.entry("a%20b").or_insert_with(|| unreachable!()) = "p&q".into();
+
+        match qs.entry("c") {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.insert("r&s".into()), "&");
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+
+        qs.entry("d").or_insert_with(|| unreachable!()).push('!');
+        qs.entry("g%26h")
+            .or_insert_with(|| unreachable!())
+            .push('=');
+
+        assert_eq!(qs.to_string(), "?a%20b=p%26q&c=r%26s&d=e%20f!&g%26h=%3D");
+    }
+
+    #[test]
+    fn test_entry_ignores_max_pairs() {
+        let mut qs = QueryString::dynamic()
+            .with_max_pairs(1)
+            .unique_keys()
+            .with_value("a", 1);
+
+        qs.entry("a").or_insert("2".into());
+        qs.entry("b").or_insert("3".into());
+        assert_eq!(qs.to_string(), "?a=1&b=3");
+    }
+}
@@ -0,0 +1,190 @@
+use crate::QueryString;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// An error indicating that a key contains a character that could break parsing
+/// of the rendered query string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidKey {
+    key: String,
+    character: char,
+    position: usize,
+}
+
+impl InvalidKey {
+    /// Checks the key for reserved delimiters and control characters.
+    pub(crate) fn check(key: &str) -> Result<(), Self> {
+        match key
+            .char_indices()
+            .find(|(_, c)| matches!(c, '&' | '=' | '#') || c.is_control())
+        {
+            Some((position, character)) => Err(Self {
+                key: key.to_string(),
+                character,
+                position,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Gets the rejected key.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Gets the offending character.
+    pub fn character(&self) -> char {
+        self.character
+    }
+
+    /// Gets the byte position of the offending character within the key.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl Display for InvalidKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid character {:?} at position {} in key {:?}",
+            self.character, self.position, self.key
+        )
+    }
+}
+
+impl Error for InvalidKey {}
+
+/// An error indicating that the rendered query string exceeds the maximum length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthExceeded {
+    actual: usize,
+    max: usize,
+}
+
+impl LengthExceeded {
+    pub(crate) fn new(actual: usize, max: usize) -> Self {
+        Self { actual, max }
+    }
+
+    /// Gets the length of the rendered query string, in bytes.
+    pub fn actual(&self) -> usize {
+        self.actual
+    }
+
+    /// Gets the maximum allowed length, in bytes.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+}
+
+impl Display for LengthExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "query string length of {} bytes exceeds the maximum of {} bytes",
+            self.actual, self.max
+        )
+    }
+}
+
+impl Error for LengthExceeded {}
+
+/// An error indicating that a value exceeds the maximum length, see
+/// [`QueryString::with_value_checked`].
+///
+/// The error retains the rejected pair and the builder, so that the value can be
+/// shortened and appended again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueTooLong {
+    key: String,
+    value: String,
+    max: usize,
+    builder: Box<QueryString>,
+}
+
+impl ValueTooLong {
+    pub(crate) fn new(key: String, value: String, max: usize, builder: QueryString) -> Self {
+        Self {
+            key,
+            value,
+            max,
+            builder: Box::new(builder),
+        }
+    }
+
+    /// Gets the decoded key of the rejected pair.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Gets the rejected decoded value.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Gets the length of the rejected decoded value, in bytes.
+    pub fn actual(&self) -> usize {
+        self.value.len()
+    }
+
+    /// Gets the maximum allowed length, in bytes.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Returns the builder the pair was rejected from, without the rejected pair.
+    pub fn into_builder(self) -> QueryString {
+        *self.builder
+    }
+}
+
+impl Display for ValueTooLong {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "value length of {} bytes for key {:?} exceeds the maximum of {} bytes",
+            self.actual(),
+            self.key,
+            self.max
+        )
+    }
+}
+
+impl Error for ValueTooLong {}
+
+/// An error indicating that a query string could not be parsed.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The input could not be read.
+    Io(std::io::Error),
+    /// A percent encoded key does not decode to valid UTF-8.
+    InvalidUtf8(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Io(e) => write!(f, "failed to read the query string: {e}"),
+            ParseError::InvalidUtf8(input) => {
+                write!(f, "{input:?} does not decode to valid UTF-8")
+            }
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::Io(e) => Some(e),
+            ParseError::InvalidUtf8(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseError {
+    fn from(value: std::io::Error) -> Self {
+        ParseError::Io(value)
+    }
+}
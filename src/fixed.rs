@@ -0,0 +1,85 @@
+use std::fmt::{self, Display, Formatter, Write};
+
+use crate::slim::fmt_pair;
+
+/// A query string builder for a fixed number of key-value pairs, created using
+/// [`QueryString::fixed`](crate::QueryString::fixed).
+///
+/// The pairs are stored inline in an array rather than a [`Vec`], and the keys and values
+/// are only percent encoded when rendering, so no heap allocations are required.
+///
+/// ## Example
+///
+/// ```
+/// use query_string_builder::QueryString;
+///
+/// let qs = QueryString::fixed([("q", "apple"), ("page", "2")]);
+///
+/// assert_eq!(qs.len(), 2);
+/// assert_eq!(qs.to_string(), "?q=apple&page=2");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FixedQueryString<K, V, const N: usize> {
+    pairs: [(K, V); N],
+}
+
+impl<K: Display, V: Display, const N: usize> FixedQueryString<K, V, N> {
+    pub(crate) fn new(pairs: [(K, V); N]) -> Self {
+        Self { pairs }
+    }
+
+    /// Determines the number of key-value pairs in the builder.
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Determines if the builder is empty.
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Returns the key-value pairs.
+    pub fn pairs(&self) -> &[(K, V); N] {
+        &self.pairs
+    }
+}
+
+impl<K: Display, V: Display, const N: usize> Display for FixedQueryString<K, V, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, (key, value)) in self.pairs.iter().enumerate() {
+            f.write_char(if i == 0 { '?' } else { '&' })?;
+            fmt_pair(key, value, f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::QueryString;
+
+    #[test]
+    fn test_fixed() {
+        let qs = QueryString::fixed([("q", "🍎 apple"), ("category", "fruits & vegetables")]);
+        assert_eq!(qs.len(), 2);
+        assert!(!qs.is_empty());
+        assert_eq!(qs.pairs()[1], ("category", "fruits & vegetables"));
+        assert_eq!(
+            qs.to_string(),
+            QueryString::simple()
+                .with_value("q", "🍎 apple")
+                .with_value("category", "fruits & vegetables")
+                .to_string()
+        );
+
+        let qs = QueryString::fixed([("id", 1), ("id", 2)]);
+        assert_eq!(qs.to_string(), "?id=1&id=2");
+    }
+
+    #[test]
+    fn test_fixed_empty() {
+        let qs = QueryString::fixed::<&str, &str, 0>([]);
+        assert!(qs.is_empty());
+        assert_eq!(qs.to_string(), "");
+    }
+}
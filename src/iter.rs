@@ -0,0 +1,92 @@
+use crate::Kvp;
+use std::iter::FusedIterator;
+
+/// An iterator over the decoded key-value pairs of a [`QueryString`](crate::QueryString).
+///
+/// This type is created by [`QueryString::iter`](crate::QueryString::iter).
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    pub(crate) inner: std::slice::Iter<'a, Kvp>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(Kvp::as_tuple)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(Kvp::as_tuple)
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+impl<'a> FusedIterator for Iter<'a> {}
+
+/// An owning iterator over the decoded key-value pairs of a [`QueryString`](crate::QueryString).
+///
+/// This type is created by the [`IntoIterator`] implementation of [`QueryString`](crate::QueryString).
+#[derive(Debug, Clone)]
+pub struct IntoIter {
+    pub(crate) inner: std::vec::IntoIter<Kvp>,
+}
+
+impl Iterator for IntoIter {
+    type Item = (String, String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(Kvp::into_tuple)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(Kvp::into_tuple)
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl FusedIterator for IntoIter {}
+
+/// A draining iterator over the decoded key-value pairs of a [`QueryString`](crate::QueryString).
+///
+/// This type is created by [`QueryString::drain`](crate::QueryString::drain).
+#[derive(Debug)]
+pub struct Drain<'a> {
+    pub(crate) inner: std::vec::Drain<'a, Kvp>,
+}
+
+impl Iterator for Drain<'_> {
+    type Item = (String, String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(Kvp::into_tuple)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Drain<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(Kvp::into_tuple)
+    }
+}
+
+impl ExactSizeIterator for Drain<'_> {}
+
+impl FusedIterator for Drain<'_> {}
@@ -0,0 +1,73 @@
+use crate::QueryString;
+use serde::Serialize;
+
+impl QueryString {
+    /// Appends a key-value pair whose value is the compact JSON serialization of `value`.
+    /// The JSON string is percent encoded like any other value when rendering.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use query_string_builder::QueryString;
+    ///
+    /// let filter = BTreeMap::from([("status", "open")]);
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_json_value("filter", &filter)
+    ///             .unwrap();
+    ///
+    /// assert_eq!(qs.get("filter"), Some(r#"{"status":"open"}"#));
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?filter={%22status%22:%22open%22}"
+    /// );
+    /// ```
+    pub fn with_json_value<K: ToString, V: Serialize + ?Sized>(
+        self,
+        key: K,
+        value: &V,
+    ) -> Result<Self, serde_json::Error> {
+        let json = serde_json::to_string(value)?;
+        Ok(self.with_value(key, json))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::QueryString;
+    use serde::ser::Error;
+    use serde::{Serialize, Serializer};
+
+    #[test]
+    fn test_json_value() {
+        let qs = QueryString::dynamic()
+            .with_json_value("ids", &[1, 2, 3])
+            .unwrap()
+            .with_json_value("q", "a&b")
+            .unwrap()
+            .with_json_value("none", &None::<u8>)
+            .unwrap();
+
+        assert_eq!(
+            qs.as_pairs(),
+            [("ids", "[1,2,3]"), ("q", "\"a&b\""), ("none", "null")]
+        );
+        assert_eq!(qs.to_string(), "?ids=[1,2,3]&q=%22a%26b%22&none=null");
+    }
+
+    #[test]
+    fn test_json_value_error() {
+        struct Failing;
+
+        impl Serialize for Failing {
+            fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+                Err(S::Error::custom("nope"))
+            }
+        }
+
+        assert!(QueryString::dynamic()
+            .with_json_value("a", &Failing)
+            .is_err());
+    }
+}
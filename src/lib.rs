@@ -22,12 +22,56 @@
 
 #![deny(unsafe_code)]
 
+mod duration;
+mod encoding;
+mod entry;
+mod error;
+mod fixed;
+mod iter;
+#[cfg(feature = "serde")]
+mod json;
+mod macros;
+mod options;
+mod param;
+mod parse;
+#[cfg(feature = "serde")]
+pub mod serialize_as_string;
 mod slim;
+#[cfg(feature = "time")]
+mod timestamp;
+#[cfg(feature = "http")]
+mod uri;
+#[cfg(feature = "url")]
+mod url_query;
 
-use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use encoding::UNRESERVED;
+use percent_encoding::{
+    percent_decode_str, percent_encode, utf8_percent_encode, AsciiSet, CONTROLS,
+};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{hash_map, BTreeMap, HashMap, HashSet};
 use std::fmt::{Debug, Display, Formatter, Write};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
-pub use slim::{QueryStringSimple, WrappedQueryString};
+use duration::DisplayDuration;
+pub use duration::DurationUnit;
+use encoding::Encoder;
+pub use encoding::{decode_value, encode_value, Encoding, ValueEncoder};
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+pub use error::{InvalidKey, LengthExceeded, ParseError, ValueTooLong};
+pub use fixed::FixedQueryString;
+pub use iter::{Drain, IntoIter, Iter};
+pub use options::QueryStringOptions;
+pub use param::QueryParam;
+pub use slim::{
+    BoolStyle, KvpSlice, PairDisplay, QueryStringSimple, StyledBool, WrappedQueryString,
+};
+#[cfg(feature = "time")]
+pub use timestamp::TimeFormat;
 
 /// https://url.spec.whatwg.org/#query-percent-encode-set
 pub(crate) const QUERY: &AsciiSet = &CONTROLS
@@ -65,6 +109,24 @@ pub(crate) const QUERY: &AsciiSet = &CONTROLS
 #[derive(Debug, Clone)]
 pub struct QueryString {
     pairs: Vec<Kvp>,
+    options: QueryStringOptions,
+    value_transform: Option<ValueTransform>,
+    leading: Option<String>,
+    leading_always: bool,
+    allowed_literal: Vec<char>,
+    custom_encoder: Option<CustomEncoder>,
+    max_pairs: Option<usize>,
+    unique_keys: bool,
+}
+
+/// The representation of pairs with an empty value used by [`QueryString::empty_value_policy`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmptyPolicy {
+    /// Renders the key followed by the key-value delimiter, e.g. `key=`.
+    #[default]
+    Equals,
+    /// Renders the bare key, e.g. `key`.
+    BareKey,
 }
 
 impl QueryString {
@@ -92,13 +154,195 @@ impl QueryString {
         QueryStringSimple::default()
     }
 
+    /// Creates a new query string builder from a slice of key-value pairs.
+    /// The slice is borrowed rather than copied.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let pairs = [("q", "apple"), ("category", "fruits and vegetables")];
+    ///
+    /// let qs = QueryString::simple_from_slice(&pairs)
+    ///             .with_value("page", 2);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple&category=fruits%20and%20vegetables&page=2"
+    /// );
+    /// ```
+    pub fn simple_from_slice<K: Display, V: Display>(
+        pairs: &[(K, V)],
+    ) -> WrappedQueryString<QueryStringSimple, KvpSlice<'_, K, V>> {
+        QueryStringSimple::default().with_slice(pairs)
+    }
+
+    /// Creates a new query string builder from an array of key-value pairs, e.g. for hot
+    /// paths with a known number of parameters. The pairs are stored inline in a
+    /// [`FixedQueryString`] and rendered without any heap allocation.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::fixed([("q", "apple"), ("category", "fruits and vegetables")]);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple&category=fruits%20and%20vegetables"
+    /// );
+    /// ```
+    pub fn fixed<K: Display, V: Display, const N: usize>(
+        pairs: [(K, V); N],
+    ) -> FixedQueryString<K, V, N> {
+        FixedQueryString::new(pairs)
+    }
+
     /// Creates a new, empty query string builder.
     pub fn dynamic() -> Self {
+        Self::with_encoding(Encoding::Query)
+    }
+
+    /// Creates a new, empty query string builder that only encodes the characters
+    /// required by RFC 3986. See [`Encoding::Rfc3986`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::rfc3986()
+    ///             .with_value("q", "1+1 #math")
+    ///             .with_value("t", "10:30");
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=1+1%20%23math&t=10:30"
+    /// );
+    /// ```
+    pub fn rfc3986() -> Self {
+        Self::with_encoding(Encoding::Rfc3986)
+    }
+
+    /// Creates a new, empty query string builder that encodes like HTML forms,
+    /// rendering spaces as `+`. See [`Encoding::Form`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::form()
+    ///             .with_value("q", "1+1 apples")
+    ///             .with_value("t", "10:30");
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=1%2B1+apples&t=10%3A30"
+    /// );
+    /// ```
+    pub fn form() -> Self {
+        Self::with_encoding(Encoding::Form)
+    }
+
+    /// Creates a new, empty builder for `Cookie` header values, which separates
+    /// the pairs using `; ` and omits the leading `?`.
+    ///
+    /// Keys and values are encoded using [`Encoding::Query`], additionally encoding `;`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let cookie = QueryString::cookie()
+    ///             .with_value("session", "a;b")
+    ///             .with_value("theme", "dark mode");
+    ///
+    /// assert_eq!(cookie.to_string(), "session=a%3Bb; theme=dark%20mode");
+    /// ```
+    pub fn cookie() -> Self {
+        Self::with_options(QueryStringOptions {
+            separator: "; ",
+            include_prefix: false,
+            ..QueryStringOptions::default()
+        })
+    }
+
+    /// Creates a new, empty query string builder using the specified encoding.
+    pub fn with_encoding(encoding: Encoding) -> Self {
+        Self::with_options(QueryStringOptions {
+            encoding,
+            ..QueryStringOptions::default()
+        })
+    }
+
+    /// Creates a new, empty query string builder using the specified options,
+    /// e.g. to share the same configuration across many builders.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::{EmptyPolicy, Encoding, QueryString, QueryStringOptions};
+    ///
+    /// let options = QueryStringOptions {
+    ///     encoding: Encoding::Rfc3986,
+    ///     separator: ";",
+    ///     kv_delimiter: ':',
+    ///     empty_policy: EmptyPolicy::BareKey,
+    ///     ..QueryStringOptions::default()
+    /// };
+    ///
+    /// let qs = QueryString::with_options(options)
+    ///             .with_value("q", "1+1")
+    ///             .with_value("verbose", "");
+    ///
+    /// assert_eq!(qs.to_string(), "?q:1+1;verbose");
+    /// assert_eq!(qs.options(), options);
+    /// ```
+    pub fn with_options(options: QueryStringOptions) -> Self {
         Self {
             pairs: Vec::default(),
+            options,
+            value_transform: None,
+            leading: None,
+            leading_always: false,
+            allowed_literal: Vec::new(),
+            custom_encoder: None,
+            max_pairs: None,
+            unique_keys: false,
         }
     }
 
+    /// Gets the options used when rendering the query string.
+    pub fn options(&self) -> QueryStringOptions {
+        self.options
+    }
+
+    /// Gets the encoding used when rendering the query string.
+    pub fn encoding(&self) -> Encoding {
+        self.options.encoding
+    }
+
+    /// Sets the encoding used when rendering the query string, keeping all pairs.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::{Encoding, QueryString};
+    ///
+    /// let mut qs = QueryString::dynamic().with_value("q", "1+1");
+    /// assert_eq!(qs.to_string(), "?q=1%2B1");
+    ///
+    /// qs.set_encoding(Encoding::Rfc3986);
+    /// assert_eq!(qs.to_string(), "?q=1+1");
+    /// ```
+    pub fn set_encoding(&mut self, mode: Encoding) {
+        self.options.encoding = mode;
+    }
+
     /// Appends a key-value pair to the query string.
     ///
     /// ## Example
@@ -117,10 +361,47 @@ impl QueryString {
     /// );
     /// ```
     pub fn with_value<K: ToString, V: ToString>(mut self, key: K, value: V) -> Self {
-        self.pairs.push(Kvp {
-            key: key.to_string(),
-            value: value.to_string(),
-        });
+        self.push_pair(Kvp::new(key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Appends a key-value pair to the query string whose value is formatted lazily,
+    /// i.e. when the query string is first rendered or the value is first looked up.
+    ///
+    /// The value is formatted at most once and cached afterwards. Clones of the builder share
+    /// the value and its cached string, so cloning does not format it again. The key is
+    /// formatted immediately since it is needed for lookups.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::fmt::{Display, Formatter};
+    /// use query_string_builder::QueryString;
+    ///
+    /// struct Expensive;
+    ///
+    /// impl Display for Expensive {
+    ///     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    ///         f.write_str("computed value")
+    ///     }
+    /// }
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_display("q", Expensive)
+    ///             .with_value("page", 2);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=computed%20value&page=2"
+    /// );
+    /// assert_eq!(qs.get("q"), Some("computed value"));
+    /// ```
+    pub fn with_display<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Display,
+        V: Display + Send + Sync + 'static,
+    {
+        self.push_pair(Kvp::lazy(key.to_string(), Box::new(value)));
         self
     }
 
@@ -150,220 +431,4564 @@ impl QueryString {
         }
     }
 
-    /// Appends a key-value pair to the query string.
+    /// Appends a key-value pair to the query string, rendering the value through a trait object.
+    ///
+    /// Unlike [`with_value`](Self::with_value), this function is not generic, which avoids
+    /// instantiating it for each distinct value type.
     ///
     /// ## Example
     ///
     /// ```
+    /// use std::fmt::Display;
     /// use query_string_builder::QueryString;
     ///
+    /// let values: [(&str, &dyn Display); 2] = [("q", &"apple"), ("weight", &99.9)];
+    ///
     /// let mut qs = QueryString::dynamic();
-    /// qs.push("q", "apple");
-    /// qs.push("category", "fruits and vegetables");
+    /// for (key, value) in values {
+    ///     qs = qs.with_value_display(key, value);
+    /// }
     ///
     /// assert_eq!(
     ///     format!("https://example.com/{qs}"),
-    ///     "https://example.com/?q=apple&category=fruits%20and%20vegetables"
+    ///     "https://example.com/?q=apple&weight=99.9"
     /// );
     /// ```
-    pub fn push<K: ToString, V: ToString>(&mut self, key: K, value: V) -> &Self {
-        self.pairs.push(Kvp {
-            key: key.to_string(),
-            value: value.to_string(),
-        });
+    pub fn with_value_display(mut self, key: &str, value: &dyn Display) -> Self {
+        self.push_pair(Kvp::new(key.to_string(), value.to_string()));
         self
     }
 
-    /// Appends a key-value pair to the query string if the value exists.
+    /// Appends a key-value pair to the query string, using an empty value if the value
+    /// does not exist. Unlike [`with_opt_value`](Self::with_opt_value), the key is always added.
     ///
     /// ## Example
     ///
     /// ```
     /// use query_string_builder::QueryString;
     ///
-    /// let mut qs = QueryString::dynamic();
-    /// qs.push_opt("q", None::<String>);
-    /// qs.push_opt("q", Some("🍎 apple"));
+    /// let qs = QueryString::dynamic()
+    ///             .with_value_or_empty("q", Some("apple"))
+    ///             .with_value_or_empty("category", None::<&str>);
     ///
     /// assert_eq!(
     ///     format!("https://example.com/{qs}"),
-    ///     "https://example.com/?q=%F0%9F%8D%8E%20apple"
+    ///     "https://example.com/?q=apple&category="
     /// );
     /// ```
-    pub fn push_opt<K: ToString, V: ToString>(&mut self, key: K, value: Option<V>) -> &Self {
-        if let Some(value) = value {
-            self.push(key, value)
-        } else {
-            self
-        }
+    pub fn with_value_or_empty<K: ToString, V: ToString>(
+        mut self,
+        key: K,
+        value: Option<V>,
+    ) -> Self {
+        let value = value.map(|value| value.to_string()).unwrap_or_default();
+        self.push_pair(Kvp::new(key.to_string(), value));
+        self
     }
 
-    /// Determines the number of key-value pairs currently in the builder.
-    pub fn len(&self) -> usize {
-        self.pairs.len()
+    /// Appends each of the key-value pairs whose value exists, in iteration order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let pairs = [
+    ///     ("q", Some("apple".to_string())),
+    ///     ("page", None),
+    ///     ("size", Some(20.to_string())),
+    /// ];
+    ///
+    /// let qs = QueryString::dynamic().with_opt_values(pairs);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple&size=20"
+    /// );
+    /// ```
+    pub fn with_opt_values<K, V, I>(mut self, pairs: I) -> Self
+    where
+        K: ToString,
+        V: ToString,
+        I: IntoIterator<Item = (K, Option<V>)>,
+    {
+        for (key, value) in pairs {
+            if let Some(value) = value {
+                self.push_pair(Kvp::new(key.to_string(), value.to_string()));
+            }
+        }
+        self
     }
 
-    /// Determines if the builder is currently empty.
-    pub fn is_empty(&self) -> bool {
-        self.pairs.is_empty()
+    /// Appends a key-value pair to the query string if the decoded value is at most
+    /// `max_len` bytes long.
+    ///
+    /// The returned error retains the builder, so that a shortened value can be appended instead.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let err = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_value_checked("note", "a very long note", 6)
+    ///             .unwrap_err();
+    ///
+    /// assert_eq!(err.key(), "note");
+    /// assert_eq!(err.actual(), 16);
+    ///
+    /// let truncated: String = err.value().chars().take(err.max()).collect();
+    /// let qs = err.into_builder()
+    ///             .with_value_checked("note", truncated, 6)
+    ///             .unwrap();
+    ///
+    /// assert_eq!(qs.to_string(), "?q=apple&note=a%20very");
+    /// ```
+    pub fn with_value_checked<K: ToString, V: ToString>(
+        self,
+        key: K,
+        value: V,
+        max_len: usize,
+    ) -> Result<Self, ValueTooLong> {
+        let (key, value) = (key.to_string(), value.to_string());
+        if value.len() > max_len {
+            return Err(ValueTooLong::new(key, value, max_len, self));
+        }
+        Ok(self.with_value(key, value))
     }
 
-    /// Appends another query string builder's values.
+    /// Appends a key-value pair to the query string if the value exists, rendering
+    /// the value through a trait object like [`with_value_display`](Self::with_value_display).
     ///
     /// ## Example
     ///
     /// ```
+    /// use std::fmt::Display;
     /// use query_string_builder::QueryString;
     ///
-    /// let mut qs = QueryString::dynamic().with_value("q", "apple");
-    /// let more = QueryString::dynamic().with_value("q", "pear");
+    /// let weight: Option<&dyn Display> = Some(&99.9);
     ///
-    /// qs.append(more);
+    /// let qs = QueryString::dynamic()
+    ///             .with_opt_value_display("q", Some(&"apple"))
+    ///             .with_opt_value_display("page", None)
+    ///             .with_opt_value_display("weight", weight);
     ///
     /// assert_eq!(
     ///     format!("https://example.com/{qs}"),
-    ///     "https://example.com/?q=apple&q=pear"
+    ///     "https://example.com/?q=apple&weight=99.9"
     /// );
     /// ```
-    pub fn append(&mut self, mut other: QueryString) {
-        self.pairs.append(&mut other.pairs)
+    pub fn with_opt_value_display(self, key: &str, value: Option<&dyn Display>) -> Self {
+        match value {
+            Some(value) => self.with_value_display(key, value),
+            None => self,
+        }
     }
 
-    /// Appends another query string builder's values, consuming both types.
+    /// Appends a key-value pair with a single character value to the query string.
     ///
     /// ## Example
     ///
     /// ```
     /// use query_string_builder::QueryString;
     ///
-    /// let qs = QueryString::dynamic().with_value("q", "apple");
-    /// let more = QueryString::dynamic().with_value("q", "pear");
+    /// let qs = QueryString::dynamic()
+    ///             .with_char("sep", ',')
+    ///             .with_char("op", '&');
     ///
-    /// let qs = qs.append_into(more);
+    /// assert_eq!(qs.to_string(), "?sep=,&op=%26");
+    /// ```
+    pub fn with_char<K: ToString>(mut self, key: K, value: char) -> Self {
+        self.push_pair(Kvp::new(key.to_string(), String::from(value)));
+        self
+    }
+
+    /// Appends a key-value pair with an arbitrary byte value, e.g. a binary token,
+    /// to the query string.
+    ///
+    /// The value is percent encoded using the builder's current configuration when it is
+    /// appended; bytes that are not part of valid UTF-8 sequences are always percent encoded.
+    /// Since the value is stored encoded, lookups such as [`get`](Self::get) return the
+    /// percent encoded value.
     ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_bytes("token", &[0x00, 0xFF, b'a', b' '])
+    ///             .with_value("q", "apple");
+    ///
+    /// assert_eq!(qs.get("token"), Some("%00%FFa%20"));
     /// assert_eq!(
     ///     format!("https://example.com/{qs}"),
-    ///     "https://example.com/?q=apple&q=pear"
+    ///     "https://example.com/?token=%00%FFa%20&q=apple"
     /// );
     /// ```
-    pub fn append_into(mut self, mut other: QueryString) -> Self {
-        self.pairs.append(&mut other.pairs);
+    pub fn with_bytes<K: ToString>(mut self, key: K, value: &[u8]) -> Self {
+        let mut encoded = String::new();
+        self.encoder()
+            .write_bytes(&mut encoded, value)
+            .expect("writing to a String never fails");
+        self.push_pair(Kvp::value_encoded(key.to_string(), encoded));
         self
     }
-}
-
-impl Display for QueryString {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.pairs.is_empty() {
-            Ok(())
-        } else {
-            f.write_char('?')?;
-            for (i, pair) in self.pairs.iter().enumerate() {
-                if i > 0 {
-                    f.write_char('&')?;
-                }
 
-                Display::fmt(&utf8_percent_encode(&pair.key, QUERY), f)?;
-                f.write_char('=')?;
-                Display::fmt(&utf8_percent_encode(&pair.value, QUERY), f)?;
-            }
-            Ok(())
-        }
+    /// Appends an already percent encoded key-value pair to the query string.
+    ///
+    /// The key and value are rendered verbatim, while all other pairs are still encoded
+    /// as usual. Note that nothing is validated: passing unencoded strings containing e.g. `&`, `=`
+    /// or `#` corrupts the query string, and lookups return the encoded strings as stored.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value_raw("token", "abc%2Fdef%3D")
+    ///             .with_value("q", "a/b=");
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?token=abc%2Fdef%3D&q=a/b%3D"
+    /// );
+    /// ```
+    pub fn with_value_raw<K: ToString, V: ToString>(mut self, key: K, value: V) -> Self {
+        self.push_pair(Kvp::raw(key.to_string(), value.to_string()));
+        self
     }
-}
-
-#[derive(Debug, Clone)]
-struct Kvp {
-    key: String,
-    value: String,
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_empty() {
-        let qs = QueryStringSimple::default();
-        assert_eq!(qs.to_string(), "");
-        assert_eq!(qs.len(), 0);
-        assert!(qs.is_empty());
+    /// Appends a key-value pair whose value is already percent encoded, e.g. an opaque token.
+    ///
+    /// Unlike [`with_value_raw`](Self::with_value_raw), the key is still encoded, and only
+    /// the value is rendered verbatim. Nothing is validated, so the value must not contain
+    /// unencoded separators such as `&` or `#`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value_preencoded("next page", "eyJpZCI6MX0%3D")
+    ///             .with_value("q", "a=b");
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?next%20page=eyJpZCI6MX0%3D&q=a%3Db"
+    /// );
+    /// ```
+    pub fn with_value_preencoded<K: ToString, V: ToString>(mut self, key: K, value: V) -> Self {
+        self.push_pair(Kvp::value_encoded(key.to_string(), value.to_string()));
+        self
     }
 
-    #[test]
-    fn test_simple() {
-        let qs = QueryString::dynamic()
-            .with_value("q", "apple???")
-            .with_value("category", "fruits and vegetables")
-            .with_value("tasty", true)
-            .with_value("weight", 99.9);
-        assert_eq!(
-            qs.to_string(),
-            "?q=apple???&category=fruits%20and%20vegetables&tasty=true&weight=99.9"
+    /// Appends a bare key without a value to the query string, e.g. `?verbose`.
+    ///
+    /// The flag is treated as having an empty value when it is looked up.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_flag("verbose");
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple&verbose"
+    /// );
+    /// ```
+    pub fn with_flag<K: ToString>(mut self, key: K) -> Self {
+        self.push_pair(Kvp::flag(key.to_string()));
+        self
+    }
+
+    /// Appends a bare key without a value to the query string, like
+    /// [`with_flag`](Self::with_flag), if `present` is `true`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_flag_opt("verbose", true)
+    ///             .with_flag_opt("debug", false);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple&verbose"
+    /// );
+    /// ```
+    pub fn with_flag_opt<K: ToString>(self, key: K, present: bool) -> Self {
+        if present {
+            self.with_flag(key)
+        } else {
+            self
+        }
+    }
+
+    /// Skips pairs with an empty value when rendering the query string.
+    ///
+    /// Bare flags added using [`with_flag`](Self::with_flag) are still rendered.
+    /// Skipped pairs remain stored in the builder, i.e. they are still counted by
+    /// [`len`](Self::len) and can still be looked up.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .skip_empty_values()
+    ///             .with_value("a", 1)
+    ///             .with_value("b", "")
+    ///             .with_flag("verbose")
+    ///             .with_value("c", 3);
+    ///
+    /// assert_eq!(qs.len(), 4);
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?a=1&verbose&c=3"
+    /// );
+    /// ```
+    pub fn skip_empty_values(mut self) -> Self {
+        self.options.skip_empty_values = true;
+        self
+    }
+
+    /// Enforces a single value per key: adding a pair whose key already exists replaces
+    /// the existing value instead of appending a duplicate, keeping the position of the
+    /// first insertion. Existing duplicates are collapsed the same way.
+    ///
+    /// This also applies to [`append`](Self::append) and [`merge`](Self::merge), and
+    /// means that functions such as [`with_array`](Self::with_array) keep only their last value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .unique_keys()
+    ///             .with_value("page", 1)
+    ///             .with_value("q", "apple")
+    ///             .with_value("page", 2);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?page=2&q=apple"
+    /// );
+    /// ```
+    pub fn unique_keys(mut self) -> Self {
+        self.unique_keys = true;
+        self.collapse_duplicate_keys();
+        self
+    }
+
+    /// Limits the number of pairs the builder accepts, e.g. to guard against runaway
+    /// parameter lists built from untrusted input.
+    ///
+    /// Once the limit is reached, subsequently added pairs are silently dropped;
+    /// use [`push_within_limit`](Self::push_within_limit) to detect this.
+    /// [`append`](Self::append) and [`merge`](Self::merge) keep only as many pairs as
+    /// the limit allows. Pairs already in the builder are kept, and the
+    /// [`entry`](Self::entry) API as well as [`get_or_insert_with`](Self::get_or_insert_with)
+    /// always insert.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_max_pairs(2)
+    ///             .with_value("a", 1)
+    ///             .with_value("b", 2)
+    ///             .with_value("c", 3);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?a=1&b=2"
+    /// );
+    /// ```
+    pub fn with_max_pairs(mut self, max: usize) -> Self {
+        self.max_pairs = Some(max);
+        self
+    }
+
+    /// Renders pairs with the same key adjacently, ordered by the first occurrence of each key.
+    /// The order of the pairs within each group is kept.
+    ///
+    /// The stored order is unchanged, i.e. this only affects rendering.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .grouped()
+    ///             .with_value("a", 1)
+    ///             .with_value("b", 2)
+    ///             .with_value("a", 3);
+    ///
+    /// assert_eq!(qs.nth(1), Some(("b", "2")));
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?a=1&a=3&b=2"
+    /// );
+    /// ```
+    pub fn grouped(mut self) -> Self {
+        self.options.grouped = true;
+        self
+    }
+
+    /// Sets how pairs with an empty value are rendered, [`EmptyPolicy::Equals`] by default.
+    ///
+    /// Unlike [`skip_empty_values`](Self::skip_empty_values), this keeps the pairs in the
+    /// rendered output. The policy applies to the value after any
+    /// [value transform](Self::with_value_transform).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::{EmptyPolicy, QueryString};
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .empty_value_policy(EmptyPolicy::BareKey)
+    ///             .with_value("a", 1)
+    ///             .with_value("b", "");
+    ///
+    /// assert_eq!(qs.get("b"), Some(""));
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?a=1&b"
+    /// );
+    /// ```
+    pub fn empty_value_policy(mut self, policy: EmptyPolicy) -> Self {
+        self.options.empty_policy = policy;
+        self
+    }
+
+    /// Sets the character written between a key and its value, `=` by default.
+    ///
+    /// The delimiter is always percent encoded when it occurs within a key or value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_kv_delimiter(':')
+    ///             .with_value("q", "apple")
+    ///             .with_value("time", "10:30");
+    ///
+    /// assert_eq!(qs.to_string(), "?q:apple&time:10%3A30");
+    /// ```
+    pub fn with_kv_delimiter(mut self, delim: char) -> Self {
+        self.options.kv_delimiter = delim;
+        self
+    }
+
+    /// Sets a custom encoder that is used for all keys and values instead of the
+    /// builder's [`Encoding`].
+    ///
+    /// The custom encoder replaces all other encoding rules, such as
+    /// [`allow_literal`](Self::allow_literal). Pairs added with
+    /// [`with_value_raw`](Self::with_value_raw) are still written verbatim.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use query_string_builder::{QueryString, ValueEncoder};
+    ///
+    /// /// Encodes only the characters that would otherwise break the query string.
+    /// struct Minimal;
+    ///
+    /// impl ValueEncoder for Minimal {
+    ///     fn encode<'a>(&self, s: &'a str) -> Cow<'a, str> {
+    ///         if s.contains(['%', '&', '=', '#', ' ']) {
+    ///             let encoded = s
+    ///                 .replace('%', "%25")
+    ///                 .replace('&', "%26")
+    ///                 .replace('=', "%3D")
+    ///                 .replace('#', "%23")
+    ///                 .replace(' ', "%20");
+    ///             Cow::Owned(encoded)
+    ///         } else {
+    ///             Cow::Borrowed(s)
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_encoder(Box::new(Minimal))
+    ///             .with_value("q", "Grünkohl & <Äpfel>");
+    ///
+    /// assert_eq!(qs.to_string(), "?q=Grünkohl%20%26%20<Äpfel>");
+    /// ```
+    pub fn with_encoder(mut self, encoder: Box<dyn ValueEncoder>) -> Self {
+        self.custom_encoder = Some(CustomEncoder(Arc::from(encoder)));
+        self
+    }
+
+    /// Sets a transformation that is applied to every value when rendering, e.g. to trim
+    /// whitespace. Keys and bare flags are not transformed.
+    ///
+    /// The stored values remain unchanged, i.e. lookups return the original values.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value_transform(|value| value.trim().to_lowercase())
+    ///             .with_value("q", " Apple ")
+    ///             .with_value("Category", "Fruits");
+    ///
+    /// assert_eq!(qs.get("q"), Some(" Apple "));
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple&Category=fruits"
+    /// );
+    /// ```
+    pub fn with_value_transform<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.value_transform = Some(ValueTransform(Arc::new(f)));
+        self
+    }
+
+    /// Appends a key-value pair with the duration rendered in the specified unit.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use query_string_builder::{DurationUnit, QueryString};
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_duration("timeout", Duration::from_secs(30), DurationUnit::Seconds)
+    ///             .with_duration("ttl", Duration::from_secs(30), DurationUnit::Millis)
+    ///             .with_duration("max_age", Duration::from_secs(90), DurationUnit::Iso8601);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?timeout=30&ttl=30000&max_age=PT1M30S"
+    /// );
+    /// ```
+    pub fn with_duration<K: ToString>(self, key: K, dur: Duration, unit: DurationUnit) -> Self {
+        self.with_value(key, DisplayDuration(dur, unit))
+    }
+
+    /// Appends a key-value pair for an IP address. If `bracket_v6` is `true`, IPv6 addresses
+    /// are wrapped in brackets like in URLs, e.g. `[::1]`.
+    ///
+    /// The address is percent encoded like any other value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_ip("v4", IpAddr::V4(Ipv4Addr::LOCALHOST), true)
+    ///             .with_ip("v6", IpAddr::V6(Ipv6Addr::LOCALHOST), true)
+    ///             .with_ip("plain", IpAddr::V6(Ipv6Addr::LOCALHOST), false);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?v4=127.0.0.1&v6=[::1]&plain=::1"
+    /// );
+    /// ```
+    pub fn with_ip<K: ToString>(self, key: K, addr: IpAddr, bracket_v6: bool) -> Self {
+        match addr {
+            IpAddr::V6(addr) if bracket_v6 => self.with_value(key, format!("[{addr}]")),
+            addr => self.with_value(key, addr),
+        }
+    }
+
+    /// Appends a key-value pair for each of the values, using the key `key[]`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_array("tags", ["fruit", "red"]);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?tags[]=fruit&tags[]=red"
+    /// );
+    /// ```
+    pub fn with_array<K: ToString, V: ToString, I: IntoIterator<Item = V>>(
+        mut self,
+        key: K,
+        values: I,
+    ) -> Self {
+        self.push_all(format!("{}[]", key.to_string()), values);
+        self
+    }
+
+    /// Appends a key-value pair for each of the values using the key `key[]`,
+    /// like [`with_array`](Self::with_array), if the values are [`Some`].
+    ///
+    /// Nothing is appended if the values are [`None`] or empty.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_opt_array("tags", Some(["fruit", "red"]))
+    ///             .with_opt_array("colors", None::<[&str; 2]>)
+    ///             .with_opt_array("sizes", Some(Vec::<u32>::new()));
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?tags[]=fruit&tags[]=red"
+    /// );
+    /// ```
+    pub fn with_opt_array<K: ToString, V: ToString, I: IntoIterator<Item = V>>(
+        self,
+        key: K,
+        values: Option<I>,
+    ) -> Self {
+        match values {
+            Some(values) => self.with_array(key, values),
+            None => self,
+        }
+    }
+
+    /// Appends the same key-value pair `count` times, e.g. `?x=1&x=1&x=1`.
+    ///
+    /// The key and value are converted to strings only once.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_repeated("x", 1, 3);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?x=1&x=1&x=1"
+    /// );
+    /// ```
+    pub fn with_repeated<K: ToString, V: ToString>(
+        mut self,
+        key: K,
+        value: V,
+        count: usize,
+    ) -> Self {
+        let pair = Kvp::new(key.to_string(), value.to_string());
+        // With unique keys, every further pair only replaces the first one.
+        let count = if self.unique_keys {
+            count.min(1)
+        } else {
+            count
+        };
+        for _ in 0..count {
+            if !self.push_pair(pair.clone()) {
+                break;
+            }
+        }
+        self
+    }
+
+    /// Sets whether spaces are rendered as `+` rather than `%20`, leaving all other
+    /// encoding rules of the [`Encoding`] intact. When enabled, a literal `+` is always
+    /// encoded as `%2B`.
+    ///
+    /// By default, spaces are rendered as `+` only for [`Encoding::Form`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::rfc3986()
+    ///             .space_as_plus(true)
+    ///             .with_value("q", "1+1 apples");
+    ///
+    /// assert_eq!(qs.to_string(), "?q=1%2B1+apples");
+    ///
+    /// let qs = QueryString::form()
+    ///             .space_as_plus(false)
+    ///             .with_value("q", "1+1 apples");
+    ///
+    /// assert_eq!(qs.to_string(), "?q=1%2B1%20apples");
+    /// ```
+    pub fn space_as_plus(mut self, enabled: bool) -> Self {
+        self.options.space_as_plus = Some(enabled);
+        self
+    }
+
+    /// Renders percent escapes using lowercase hexadecimal digits, e.g. `%c3%bc` instead
+    /// of `%C3%BC`. Literal characters are not affected.
+    ///
+    /// This does not apply to pre-encoded pairs or [custom encoders](Self::with_encoder).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .lowercase_encoding()
+    ///             .with_value("q", "Grünkohl & Äpfel");
+    ///
+    /// assert_eq!(qs.to_string(), "?q=Gr%c3%bcnkohl%20%26%20%c3%84pfel");
+    /// ```
+    pub fn lowercase_encoding(mut self) -> Self {
+        self.options.lowercase_encoding = true;
+        self
+    }
+
+    /// Additionally percent encodes `/`, `?`, `:` and `@` in keys and values, e.g. to pass
+    /// a callback URL as an opaque value.
+    ///
+    /// This does not apply to pre-encoded pairs or [custom encoders](Self::with_encoder).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .encode_path_chars()
+    ///             .with_value("callback", "https://example.com/done?user=me@example.com");
+    ///
+    /// assert_eq!(
+    ///     qs.to_string(),
+    ///     "?callback=https%3A%2F%2Fexample.com%2Fdone%3Fuser%3Dme%40example.com"
+    /// );
+    /// ```
+    pub fn encode_path_chars(mut self) -> Self {
+        self.options.encode_path_chars = true;
+        self
+    }
+
+    /// Exempts the specified characters from percent encoding in keys and values,
+    /// while keeping all other encoding rules intact.
+    ///
+    /// Allowing characters that delimit the pairs, such as `&` or `=`, makes the
+    /// rendered query string ambiguous.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::form()
+    ///             .allow_literal(&[':', '~'])
+    ///             .with_value("from", "10:30")
+    ///             .with_value("user", "~me");
+    ///
+    /// assert_eq!(qs.to_string(), "?from=10:30&user=~me");
+    /// ```
+    pub fn allow_literal(mut self, chars: &[char]) -> Self {
+        self.allowed_literal.extend_from_slice(chars);
+        self
+    }
+
+    /// Sets a string that is written verbatim, i.e. without percent encoding, between the `?`
+    /// and the first pair. Nothing is rendered if the query string is empty;
+    /// use [`with_leading_always`](Self::with_leading_always) to render it regardless.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic().with_leading("v2;");
+    /// assert_eq!(qs.to_string(), "");
+    ///
+    /// let qs = qs.with_value("a", 1).with_value("b", 2);
+    /// assert_eq!(qs.to_string(), "?v2;a=1&b=2");
+    /// ```
+    pub fn with_leading(mut self, s: &str) -> Self {
+        self.leading = Some(s.to_string());
+        self.leading_always = false;
+        self
+    }
+
+    /// Sets a string that is written verbatim, i.e. without percent encoding, between the `?`
+    /// and the first pair. Unlike [`with_leading`](Self::with_leading), the `?` and the string
+    /// are rendered even if the query string is empty.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic().with_leading_always("v2;");
+    /// assert_eq!(qs.to_string(), "?v2;");
+    /// ```
+    pub fn with_leading_always(mut self, s: &str) -> Self {
+        self.leading = Some(s.to_string());
+        self.leading_always = true;
+        self
+    }
+
+    /// Appends a key-value pair for each of the values, using zero-based indexed keys
+    /// of the form `key[0]`, `key[1]`, and so on. Indices start at zero for every call.
+    ///
+    /// The brackets are percent encoded, e.g. `key%5B0%5D`. Since the keys are encoded
+    /// using the builder's current configuration when they are appended, lookups such as
+    /// [`get`](Self::get) use the percent encoded key.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_indexed_array("tags", ["fruit", "red"]);
+    ///
+    /// assert_eq!(qs.get("tags%5B1%5D"), Some("red"));
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?tags%5B0%5D=fruit&tags%5B1%5D=red"
+    /// );
+    /// ```
+    pub fn with_indexed_array<K: ToString, V: ToString, I: IntoIterator<Item = V>>(
+        mut self,
+        key: K,
+        values: I,
+    ) -> Self {
+        let mut key_encoded = String::new();
+        self.encoder()
+            .write(&mut key_encoded, &key.to_string())
+            .expect("writing to a String never fails");
+        let (open, close) = if self.options.lowercase_encoding {
+            ("%5b", "%5d")
+        } else {
+            ("%5B", "%5D")
+        };
+
+        for (i, value) in values.into_iter().enumerate() {
+            let key = format!("{key_encoded}{open}{i}{close}");
+            self.push_pair(Kvp::key_encoded(key, value.to_string()));
+        }
+        self
+    }
+
+    /// Appends a key-value pair to the query string, using the key `namespace[key]`.
+    ///
+    /// This is useful for APIs that flatten nested objects into bracketed keys.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_namespaced("filter", "status", "open")
+    ///             .with_namespaced("filter", "owner", "me");
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?filter[status]=open&filter[owner]=me"
+    /// );
+    /// ```
+    pub fn with_namespaced<K: ToString, V: ToString>(
+        self,
+        namespace: &str,
+        key: K,
+        value: V,
+    ) -> Self {
+        let key = format!("{namespace}[{}]", key.to_string());
+        self.with_value(key, value)
+    }
+
+    /// Appends a key-value pair to the query string.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic();
+    /// qs.push("q", "apple");
+    /// qs.push("category", "fruits and vegetables");
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple&category=fruits%20and%20vegetables"
+    /// );
+    /// ```
+    pub fn push<K: ToString, V: ToString>(&mut self, key: K, value: V) -> &Self {
+        self.push_pair(Kvp::new(key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Appends a key-value pair to the query string, formatting the key from the
+    /// specified arguments, e.g. to generate keys in a loop.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic();
+    /// for (i, fruit) in ["apple", "pear"].iter().enumerate() {
+    ///     qs.push_key_fmt(format_args!("item_{i}"), fruit);
+    /// }
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?item_0=apple&item_1=pear"
+    /// );
+    /// ```
+    pub fn push_key_fmt<V: ToString>(&mut self, key: std::fmt::Arguments<'_>, value: V) -> &Self {
+        self.push_pair(Kvp::new(std::fmt::format(key), value.to_string()));
+        self
+    }
+
+    /// Appends a key-value pair to the query string after validating the key.
+    ///
+    /// Keys containing the reserved delimiters `&`, `=` or `#`, or any control characters,
+    /// are rejected. Use [`push`](Self::push) to accept any key and have it percent encoded.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic();
+    /// assert!(qs.try_push("q", "apple").is_ok());
+    ///
+    /// let err = qs.try_push("a&b", "pear").unwrap_err();
+    /// assert_eq!(err.character(), '&');
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple"
+    /// );
+    /// ```
+    pub fn try_push<K: ToString, V: ToString>(
+        &mut self,
+        key: K,
+        value: V,
+    ) -> Result<&mut Self, InvalidKey> {
+        let key = key.to_string();
+        InvalidKey::check(&key)?;
+        self.push_pair(Kvp::new(key, value.to_string()));
+        Ok(self)
+    }
+
+    /// Appends a key-value pair to the query string unless the builder reached the limit
+    /// set using [`with_max_pairs`](Self::with_max_pairs). Returns whether the pair was added.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic().with_max_pairs(1);
+    ///
+    /// assert!(qs.push_within_limit("q", "apple"));
+    /// assert!(!qs.push_within_limit("q", "pear"));
+    ///
+    /// assert_eq!(qs.to_string(), "?q=apple");
+    /// ```
+    pub fn push_within_limit<K: ToString, V: ToString>(&mut self, key: K, value: V) -> bool {
+        self.push_pair(Kvp::new(key.to_string(), value.to_string()))
+    }
+
+    /// Appends a key-value pair to the query string if the value exists.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic();
+    /// qs.push_opt("q", None::<String>);
+    /// qs.push_opt("q", Some("🍎 apple"));
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=%F0%9F%8D%8E%20apple"
+    /// );
+    /// ```
+    pub fn push_opt<K: ToString, V: ToString>(&mut self, key: K, value: Option<V>) -> &Self {
+        if let Some(value) = value {
+            self.push(key, value)
+        } else {
+            self
+        }
+    }
+
+    /// Appends a key-value pair to the query string unless a pair with the same key
+    /// already exists. Returns whether the pair was added.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic().with_value("page", 3);
+    ///
+    /// assert!(!qs.push_if_absent("page", 1));
+    /// assert!(qs.push_if_absent("size", 20));
+    ///
+    /// assert_eq!(qs.to_string(), "?page=3&size=20");
+    /// ```
+    pub fn push_if_absent<K: ToString, V: ToString>(&mut self, key: K, value: V) -> bool {
+        let key = key.to_string();
+        if self.contains_key(&key) {
+            return false;
+        }
+
+        self.push_pair(Kvp::new(key, value.to_string()))
+    }
+
+    /// Appends a key-value pair for each of the values, repeating the same key.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic();
+    /// qs.push_all("id", [1, 2, 3]);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?id=1&id=2&id=3"
+    /// );
+    /// ```
+    pub fn push_all<K: ToString, V: ToString, I: IntoIterator<Item = V>>(
+        &mut self,
+        key: K,
+        values: I,
+    ) -> &Self {
+        let key = key.to_string();
+        for value in values {
+            self.push_pair(Kvp::new(key.clone(), value.to_string()));
+        }
+        self
+    }
+
+    /// Determines the number of key-value pairs currently in the builder.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Determines if the builder is currently empty.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Determines the number of key-value pairs the builder can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.pairs.capacity()
+    }
+
+    /// Shrinks the capacity of the builder as much as possible, e.g. after removing
+    /// many pairs from a long-lived builder.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic();
+    /// for i in 0..100 {
+    ///     qs.push("page", i);
+    /// }
+    ///
+    /// let _ = qs.split_off(2);
+    /// assert!(qs.capacity() >= 100);
+    ///
+    /// qs.shrink_to_fit();
+    /// assert!(qs.capacity() < 100);
+    /// assert_eq!(qs.to_string(), "?page=0&page=1");
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.pairs.shrink_to_fit();
+    }
+
+    /// Returns an iterator over the decoded key-value pairs in insertion order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "🍎 apple")
+    ///             .with_value("page", 2);
+    ///
+    /// let keys: Vec<_> = qs.iter().map(|(key, _)| key).collect();
+    /// assert_eq!(keys, ["q", "page"]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.pairs.iter(),
+        }
+    }
+
+    /// Returns an iterator over the decoded keys in insertion order.
+    ///
+    /// Keys are repeated if multiple pairs share the same key.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "🍎 apple")
+    ///             .with_value("page", 2);
+    ///
+    /// assert!(qs.keys().all(|key| ["q", "page", "size"].contains(&key)));
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = &str> + '_ {
+        self.pairs.iter().map(|pair| pair.key.as_str())
+    }
+
+    /// Returns an iterator over the decoded values in insertion order.
+    ///
+    /// Bare flags yield an empty value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "🍎 apple")
+    ///             .with_value("page", 2);
+    ///
+    /// let values: Vec<_> = qs.values().collect();
+    /// assert_eq!(values, ["🍎 apple", "2"]);
+    /// ```
+    pub fn values(&self) -> impl Iterator<Item = &str> + '_ {
+        self.pairs.iter().map(Kvp::value)
+    }
+
+    /// Removes all pairs, yielding the decoded key-value pairs in insertion order.
+    /// This mirrors [`Vec::drain`] for the full range.
+    ///
+    /// The builder is empty afterwards, even if the iterator is dropped before it is
+    /// fully consumed. The configuration is retained.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic()
+    ///             .with_value("q", "🍎 apple")
+    ///             .with_value("page", 2);
+    ///
+    /// let pairs: Vec<(String, String)> = qs.drain().collect();
+    ///
+    /// assert_eq!(pairs[0], ("q".to_string(), "🍎 apple".to_string()));
+    /// assert!(qs.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_> {
+        Drain {
+            inner: self.pairs.drain(..),
+        }
+    }
+
+    /// Returns the decoded key-value pairs as borrowed string slices.
+    ///
+    /// This is useful for handing the parameters to APIs that accept `&[(&str, &str)]`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "🍎 apple")
+    ///             .with_value("page", 2);
+    ///
+    /// assert_eq!(qs.as_pairs(), [("q", "🍎 apple"), ("page", "2")]);
+    /// ```
+    pub fn as_pairs(&self) -> Vec<(&str, &str)> {
+        self.iter().collect()
+    }
+
+    /// Gets the first decoded value for the specified key.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_value("q", "pear");
+    ///
+    /// assert_eq!(qs.get("q"), Some("apple"));
+    /// assert_eq!(qs.get("Q"), None);
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|pair| pair.key == key)
+            .map(Kvp::value)
+    }
+
+    /// Gets the first decoded value for the specified key and parses it into `T`.
+    ///
+    /// Returns [`None`] if the key does not exist.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::parse("?page=2&size=large").unwrap();
+    ///
+    /// assert_eq!(qs.get_parsed::<u32>("page"), Some(Ok(2)));
+    /// assert!(matches!(qs.get_parsed::<u32>("size"), Some(Err(_))));
+    /// assert_eq!(qs.get_parsed::<u32>("offset"), None);
+    /// ```
+    pub fn get_parsed<T: FromStr>(&self, key: &str) -> Option<Result<T, T::Err>> {
+        self.get(key).map(str::parse)
+    }
+
+    /// Gets the first value for the specified key as decoded bytes, which need not be
+    /// valid UTF-8.
+    ///
+    /// Values that are stored percent encoded, i.e. added using [`with_bytes`](Self::with_bytes),
+    /// [`with_value_preencoded`](Self::with_value_preencoded),
+    /// [`with_value_raw`](Self::with_value_raw) or
+    /// [`with_raw_fragment_verbatim`](Self::with_raw_fragment_verbatim), or parsed values that
+    /// are not valid UTF-8, are percent decoded.
+    /// All other values are returned as-is.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::parse("?sig=%DE%AD%BE%EF&q=apple").unwrap();
+    ///
+    /// assert_eq!(qs.get_bytes("sig"), Some(vec![0xDE, 0xAD, 0xBE, 0xEF]));
+    /// assert_eq!(qs.get_bytes("q"), Some(b"apple".to_vec()));
+    /// assert_eq!(qs.get_bytes("page"), None);
+    /// ```
+    pub fn get_bytes(&self, key: &str) -> Option<Vec<u8>> {
+        let pair = self.pairs.iter().find(|pair| pair.key == key)?;
+        if pair.raw || pair.value_encoded {
+            Some(percent_decode_str(pair.value()).collect())
+        } else {
+            Some(pair.value().as_bytes().to_vec())
+        }
+    }
+
+    /// Determines whether a pair with the specified key exists.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.pairs.iter().any(|pair| pair.key == key)
+    }
+
+    /// Counts the pairs with the specified key, e.g. the values of a repeated key.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("tag", "rust")
+    ///             .with_value("q", "apple")
+    ///             .with_value("tag", "web");
+    ///
+    /// assert_eq!(qs.count_key("tag"), 2);
+    /// assert_eq!(qs.count_key("page"), 0);
+    /// ```
+    pub fn count_key(&self, key: &str) -> usize {
+        self.pairs.iter().filter(|pair| pair.key == key).count()
+    }
+
+    /// Gets the first decoded value for the specified key, comparing keys
+    /// using ASCII case-insensitive matching.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic().with_value("Page", 2);
+    ///
+    /// assert_eq!(qs.get_ignore_case("page"), Some("2"));
+    /// ```
+    pub fn get_ignore_case(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|pair| pair.key.eq_ignore_ascii_case(key))
+            .map(Kvp::value)
+    }
+
+    /// Determines whether a pair with the specified key exists, comparing keys
+    /// using ASCII case-insensitive matching.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic().with_value("Page", 2);
+    ///
+    /// assert!(qs.contains_key_ignore_case("PAGE"));
+    /// assert!(!qs.contains_key("PAGE"));
+    /// ```
+    pub fn contains_key_ignore_case(&self, key: &str) -> bool {
+        self.pairs
+            .iter()
+            .any(|pair| pair.key.eq_ignore_ascii_case(key))
+    }
+
+    /// Gets the first decoded value for the specified key, appending a pair with the
+    /// computed default value if the key does not exist yet.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic().with_value("page", 3);
+    ///
+    /// assert_eq!(qs.get_or_insert_with("page", || 1), "3");
+    /// assert_eq!(qs.get_or_insert_with("size", || 20), "20");
+    ///
+    /// assert_eq!(qs.to_string(), "?page=3&size=20");
+    /// ```
+    pub fn get_or_insert_with<K: ToString, V: ToString, F: FnOnce() -> V>(
+        &mut self,
+        key: K,
+        default: F,
+    ) -> &str {
+        let key = key.to_string();
+        let index = match self.pairs.iter().position(|pair| pair.key == key) {
+            Some(index) => index,
+            None => {
+                self.pairs.push(Kvp::new(key, default().to_string()));
+                self.pairs.len() - 1
+            }
+        };
+        self.pairs[index].value()
+    }
+
+    /// Gets the entry of the first pair with the specified key for in-place manipulation,
+    /// similar to [`HashMap::entry`](std::collections::HashMap::entry).
+    ///
+    /// Inserting into a vacant entry always appends the pair, even if the builder reached
+    /// the limit set using [`with_max_pairs`](Self::with_max_pairs), since a reference to
+    /// the inserted value is returned. [`unique_keys`](Self::unique_keys) is upheld, since
+    /// a vacant entry's key does not exist yet.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic().with_value("page", 1);
+    ///
+    /// qs.entry("page").and_modify(|v| *v = "2".into()).or_insert("1".into());
+    /// qs.entry("size").and_modify(|v| *v = "50".into()).or_insert("20".into());
+    ///
+    /// assert_eq!(qs.to_string(), "?page=2&size=20");
+    /// ```
+    pub fn entry<K: ToString>(&mut self, key: K) -> Entry<'_> {
+        let key = key.to_string();
+        match self.pairs.iter().position(|pair| pair.key == key) {
+            Some(index) => Entry::Occupied(OccupiedEntry {
+                pair: &mut self.pairs[index],
+            }),
+            None => Entry::Vacant(VacantEntry {
+                key,
+                pairs: &mut self.pairs,
+            }),
+        }
+    }
+
+    /// Gets the first decoded key-value pair, if any.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_value("page", 2);
+    ///
+    /// assert_eq!(qs.first(), Some(("q", "apple")));
+    /// ```
+    pub fn first(&self) -> Option<(&str, &str)> {
+        self.pairs.first().map(Kvp::as_tuple)
+    }
+
+    /// Gets the last decoded key-value pair, if any.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_value("page", 2);
+    ///
+    /// assert_eq!(qs.last(), Some(("page", "2")));
+    /// ```
+    pub fn last(&self) -> Option<(&str, &str)> {
+        self.pairs.last().map(Kvp::as_tuple)
+    }
+
+    /// Gets the decoded key-value pair at the specified position, if any.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_value("page", 2);
+    ///
+    /// assert_eq!(qs.nth(1), Some(("page", "2")));
+    /// assert_eq!(qs.nth(2), None);
+    /// ```
+    pub fn nth(&self, index: usize) -> Option<(&str, &str)> {
+        self.pairs.get(index).map(Kvp::as_tuple)
+    }
+
+    /// Clones the decoded key-value pairs into owned tuples, in insertion order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic().with_value("page", 2);
+    ///
+    /// assert_eq!(qs.to_vec(), [("page".to_string(), "2".to_string())]);
+    /// ```
+    pub fn to_vec(&self) -> Vec<(String, String)> {
+        self.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    /// Groups the decoded values by their decoded key, keeping the order of the values of each key.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::parse("?tag=rust&q=apple&tag=web").unwrap();
+    /// let map = qs.to_multimap();
+    ///
+    /// assert_eq!(map["tag"], ["rust", "web"]);
+    /// assert_eq!(map["q"], ["apple"]);
+    /// ```
+    pub fn to_multimap(&self) -> HashMap<String, Vec<String>> {
+        let mut map: HashMap<String, Vec<String>> = HashMap::new();
+        for (key, value) in self.iter() {
+            map.entry(key.to_string())
+                .or_default()
+                .push(value.to_string());
+        }
+        map
+    }
+
+    /// Groups the decoded values by their decoded key like [`to_multimap`](Self::to_multimap),
+    /// ordering the keys for deterministic iteration.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::parse("?tag=rust&q=apple&tag=web").unwrap();
+    /// let map = qs.to_sorted_multimap();
+    ///
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), ["q", "tag"]);
+    /// assert_eq!(map["tag"], ["rust", "web"]);
+    /// ```
+    pub fn to_sorted_multimap(&self) -> BTreeMap<String, Vec<String>> {
+        let mut map: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (key, value) in self.iter() {
+            map.entry(key.to_string())
+                .or_default()
+                .push(value.to_string());
+        }
+        map
+    }
+
+    /// Appends another query string builder's values.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic().with_value("q", "apple");
+    /// let more = QueryString::dynamic().with_value("q", "pear");
+    ///
+    /// qs.append(more);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple&q=pear"
+    /// );
+    /// ```
+    pub fn append(&mut self, mut other: QueryString) {
+        self.pairs.append(&mut other.pairs);
+        self.collapse_duplicate_keys();
+        self.truncate_to_max_pairs();
+    }
+
+    /// Appends another query string builder's values, consuming both types.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic().with_value("q", "apple");
+    /// let more = QueryString::dynamic().with_value("q", "pear");
+    ///
+    /// let qs = qs.append_into(more);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple&q=pear"
+    /// );
+    /// ```
+    pub fn append_into(mut self, other: QueryString) -> Self {
+        self.append(other);
+        self
+    }
+
+    /// Appends copies of the borrowed key-value pairs, e.g. a selection of the pairs of
+    /// another query string builder that should be kept intact.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let template = QueryString::dynamic()
+    ///             .with_value("api_key", "secret")
+    ///             .with_value("lang", "en")
+    ///             .with_value("region", "eu");
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_pairs_from(template.iter().filter(|(key, _)| *key != "api_key"));
+    ///
+    /// assert_eq!(template.len(), 3);
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple&lang=en&region=eu"
+    /// );
+    /// ```
+    pub fn with_pairs_from<'a, I: IntoIterator<Item = (&'a str, &'a str)>>(
+        mut self,
+        pairs: I,
+    ) -> Self {
+        for (key, value) in pairs {
+            self.push_pair(Kvp::new(key.to_string(), value.to_string()));
+        }
+        self
+    }
+
+    /// Renders the query string into a byte buffer, e.g. for assembling a raw HTTP request line.
+    ///
+    /// The bytes are identical to the output of the [`Display`] implementation.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple pie")
+    ///             .with_value("page", 2);
+    ///
+    /// let mut request = b"GET /search".to_vec();
+    /// request.extend(qs.to_bytes());
+    ///
+    /// assert_eq!(request, b"GET /search?q=apple%20pie&page=2");
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_string().into_bytes()
+    }
+
+    /// Renders the pairs as a URL fragment, i.e. with a leading `#` instead of `?`,
+    /// e.g. for hash-based routes of single-page applications.
+    ///
+    /// The pairs are encoded exactly like in the query string. An empty builder renders
+    /// an empty string.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("view", "grid")
+    ///             .with_value("sort", "name");
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/app{}", qs.as_fragment()),
+    ///     "https://example.com/app#view=grid&sort=name"
+    /// );
+    /// ```
+    pub fn as_fragment(&self) -> String {
+        let mut result = String::new();
+        self.write_encoded(&mut result, "#")
+            .expect("writing to a String never fails");
+        result
+    }
+
+    /// Renders the query string without applying any percent encoding.
+    ///
+    /// This is intended for debugging and logging only, since the output is not a valid
+    /// query string when keys or values contain reserved or non-ASCII characters.
+    /// Use the [`Display`] implementation to produce the string sent over the wire.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "🍎 apple")
+    ///             .with_value("category", "fruits & vegetables");
+    ///
+    /// assert_eq!(qs.to_decoded_string(), "?q=🍎 apple&category=fruits & vegetables");
+    /// ```
+    pub fn to_decoded_string(&self) -> String {
+        let mut result = String::new();
+        self.render(&mut result, self.prefix(), |w, part, _| w.write_str(part))
+            .expect("writing to a String never fails");
+        result
+    }
+
+    /// Renders the query string, failing if it exceeds `max_len` bytes.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic().with_value("q", "apple");
+    ///
+    /// assert_eq!(qs.try_to_string(10).unwrap(), "?q=apple");
+    ///
+    /// let err = qs.try_to_string(5).unwrap_err();
+    /// assert_eq!(err.actual(), 8);
+    /// assert_eq!(err.max(), 5);
+    /// ```
+    pub fn try_to_string(&self, max_len: usize) -> Result<String, LengthExceeded> {
+        let rendered = self.to_string();
+        if rendered.len() > max_len {
+            return Err(LengthExceeded::new(rendered.len(), max_len));
+        }
+        Ok(rendered)
+    }
+
+    /// Compares the decoded pairs of both builders in order, ignoring all pairs with one
+    /// of the specified keys, e.g. volatile parameters such as nonces.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let a = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_value("nonce", 1234);
+    /// let b = QueryString::dynamic()
+    ///             .with_value("nonce", 5678)
+    ///             .with_value("q", "apple");
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.eq_ignoring(&b, &["nonce"]));
+    /// ```
+    pub fn eq_ignoring(&self, other: &QueryString, ignored_keys: &[&str]) -> bool {
+        let retained = |(key, _): &(&str, &str)| !ignored_keys.contains(key);
+        self.iter()
+            .filter(retained)
+            .eq(other.iter().filter(retained))
+    }
+
+    /// Determines whether the decoded pairs are sorted by key, then by value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("a", 2)
+    ///             .with_value("b", 1);
+    ///
+    /// assert!(qs.is_sorted());
+    /// assert!(!qs.with_value("a", 1).is_sorted());
+    /// ```
+    pub fn is_sorted(&self) -> bool {
+        self.iter().zip(self.iter().skip(1)).all(|(a, b)| a <= b)
+    }
+
+    /// Renders the canonical query string used for request signing, compatible with
+    /// AWS Signature Version 4.
+    ///
+    /// All keys and values are encoded such that only unreserved characters (`A-Z`, `a-z`,
+    /// `0-9`, `-`, `.`, `_` and `~`) are kept literal, using uppercase hexadecimal digits
+    /// for everything else. The pairs are then sorted by encoded key, then by encoded value,
+    /// and joined using `&` without a leading `?`. Bare flags are rendered with an empty value.
+    ///
+    /// Only the [value transform](Self::with_value_transform) and
+    /// [`skip_empty_values`](Self::skip_empty_values) are applied; all other rendering
+    /// options and the [`Encoding`] are ignored. Pre-encoded keys and values are decoded
+    /// and encoded again.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("X-Amz-Date", "20240501T123000Z")
+    ///             .with_value("prefix", "photos/2024 ~ä")
+    ///             .with_value("list-type", 2)
+    ///             .with_flag("acl");
+    ///
+    /// assert_eq!(
+    ///     qs.to_canonical(),
+    ///     "X-Amz-Date=20240501T123000Z&acl=&list-type=2&prefix=photos%2F2024%20~%C3%A4"
+    /// );
+    /// ```
+    pub fn to_canonical(&self) -> String {
+        fn encode(value: &str, encoded: bool) -> String {
+            if encoded {
+                let decoded: Vec<u8> = percent_decode_str(value).collect();
+                percent_encode(&decoded, UNRESERVED).to_string()
+            } else {
+                utf8_percent_encode(value, UNRESERVED).to_string()
+            }
+        }
+
+        let mut pairs: Vec<(String, String)> = Vec::with_capacity(self.pairs.len());
+        for pair in &self.pairs {
+            let value = match &self.value_transform {
+                Some(transform) if !pair.flag => Cow::Owned(transform.apply(pair.value())),
+                _ => Cow::Borrowed(pair.value()),
+            };
+
+            if self.options.skip_empty_values && !pair.flag && value.is_empty() {
+                continue;
+            }
+
+            pairs.push((
+                encode(&pair.key, pair.is_key_encoded()),
+                encode(&value, pair.raw || pair.value_encoded),
+            ));
+        }
+        pairs.sort_unstable();
+
+        let mut canonical = String::new();
+        for (i, (key, value)) in pairs.iter().enumerate() {
+            if i > 0 {
+                canonical.push('&');
+            }
+            canonical.push_str(key);
+            canonical.push('=');
+            canonical.push_str(value);
+        }
+        canonical
+    }
+
+    /// Determines the exact length in bytes of the rendered query string, including
+    /// the leading `?` and all separators, without rendering it into a `String`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple pie")
+    ///             .with_value("page", 2);
+    ///
+    /// assert_eq!(qs.rendered_len(), "?q=apple%20pie&page=2".len());
+    /// assert_eq!(QueryString::dynamic().rendered_len(), 0);
+    /// ```
+    pub fn rendered_len(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        self.write_into(&mut counter)
+            .expect("counting bytes never fails");
+        counter.0
+    }
+
+    /// Merges another query string builder's values using last-wins semantics.
+    ///
+    /// Every key of `other` replaces all pairs of the same key in this builder,
+    /// taking the position of the first replaced pair. Keys not yet present are appended
+    /// at the end. Unlike [`append`](Self::append), this never produces duplicate keys
+    /// that were not already present in either builder.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic()
+    ///             .with_value("timeout", 30)
+    ///             .with_value("q", "apple");
+    /// let overrides = QueryString::dynamic()
+    ///             .with_value("timeout", 5)
+    ///             .with_value("page", 2);
+    ///
+    /// qs.merge(overrides);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?timeout=5&q=apple&page=2"
+    /// );
+    /// ```
+    pub fn merge(&mut self, other: QueryString) {
+        let mut other = other.pairs;
+        let mut pairs = Vec::with_capacity(self.pairs.len() + other.len());
+        let mut replaced = HashSet::new();
+
+        for pair in std::mem::take(&mut self.pairs) {
+            if replaced.contains(&pair.key) {
+                continue;
+            }
+
+            if other.iter().any(|o| o.key == pair.key) {
+                let (matching, rest) = other.into_iter().partition(|o| o.key == pair.key);
+                other = rest;
+                pairs.extend(matching);
+                replaced.insert(pair.key);
+            } else {
+                pairs.push(pair);
+            }
+        }
+
+        pairs.extend(other);
+        self.pairs = pairs;
+        self.collapse_duplicate_keys();
+        self.truncate_to_max_pairs();
+    }
+
+    /// Replaces every stored value with the result of `f`, e.g. to truncate values.
+    /// Keys and bare flags are not changed.
+    ///
+    /// `f` receives the decoded value, also for values that were added already percent
+    /// encoded, e.g. using [`with_bytes`](Self::with_bytes). The returned value is always
+    /// percent encoded when rendering.
+    ///
+    /// Unlike [`with_value_transform`](Self::with_value_transform), this changes the stored
+    /// values once rather than transforming them whenever the query string is rendered.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic()
+    ///             .with_value("q", " apple ")
+    ///             .with_value("note", "a very long note");
+    ///
+    /// qs.map_values(|value| value.trim().chars().take(6).collect());
+    ///
+    /// assert_eq!(qs.get("q"), Some("apple"));
+    /// assert_eq!(qs.to_string(), "?q=apple&note=a%20very");
+    /// ```
+    pub fn map_values<F: FnMut(&str) -> String>(&mut self, mut f: F) {
+        for pair in self.pairs.iter_mut().filter(|pair| !pair.flag) {
+            let value = f(&pair.decoded_value());
+            pair.set_value(value);
+        }
+    }
+
+    /// Replaces every stored key with the result of `f`, e.g. to normalize the case of keys.
+    ///
+    /// Like [`map_values`](Self::map_values), `f` receives the decoded key and the returned
+    /// key is always percent encoded when rendering. If the builder enforces
+    /// [`unique_keys`](Self::unique_keys), keys that become equal are collapsed, keeping
+    /// the last value at the position of the first occurrence.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic()
+    ///             .with_value("Query", "apple")
+    ///             .with_flag("DEBUG");
+    ///
+    /// qs.map_keys(str::to_lowercase);
+    ///
+    /// assert_eq!(qs.to_string(), "?query=apple&debug");
+    /// ```
+    pub fn map_keys<F: FnMut(&str) -> String>(&mut self, mut f: F) {
+        for pair in &mut self.pairs {
+            let key = f(&pair.decoded_key());
+            pair.set_key(key);
+        }
+        self.collapse_duplicate_keys();
+    }
+
+    /// Removes all pairs with an empty key, e.g. `=value` from malformed input.
+    /// Returns the number of removed pairs.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::parse("?=1&q=apple&=2").unwrap();
+    ///
+    /// assert_eq!(qs.remove_empty_keys(), 2);
+    /// assert_eq!(qs.to_string(), "?q=apple");
+    /// ```
+    pub fn remove_empty_keys(&mut self) -> usize {
+        let len = self.pairs.len();
+        self.pairs.retain(|pair| !pair.key.is_empty());
+        len - self.pairs.len()
+    }
+
+    /// Prepends `prefix` to the keys of all pairs, e.g. to namespace the parameters of a
+    /// sub-component before [`append`](QueryString::append)ing them to another builder.
+    ///
+    /// The prefix is applied to the decoded keys. For pairs added with
+    /// [`with_value_raw`](QueryString::with_value_raw), the encoded prefix is prepended.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let filter = QueryString::dynamic()
+    ///             .with_value("status", "open")
+    ///             .with_value("tag", "rust");
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .append_into(filter.prefix_keys("filter_"));
+    ///
+    /// assert_eq!(qs.to_string(), "?q=apple&filter_status=open&filter_tag=rust");
+    /// ```
+    pub fn prefix_keys(mut self, prefix: &str) -> Self {
+        let mut encoded = String::new();
+        self.encoder()
+            .write(&mut encoded, prefix)
+            .expect("writing to a String never fails");
+        for pair in &mut self.pairs {
+            let prefix = if pair.is_key_encoded() {
+                &encoded
+            } else {
+                prefix
+            };
+            pair.key.insert_str(0, prefix);
+        }
+        self
+    }
+
+    /// Returns a new builder containing copies of the pairs whose decoded key satisfies
+    /// the predicate, keeping this builder intact. The configuration is retained.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_value("debug", 1)
+    ///             .with_value("page", 2);
+    ///
+    /// let public = qs.filter_keys(|key| key != "debug");
+    ///
+    /// assert_eq!(public.to_string(), "?q=apple&page=2");
+    /// assert_eq!(qs.len(), 3);
+    /// ```
+    pub fn filter_keys<F: Fn(&str) -> bool>(&self, f: F) -> QueryString {
+        let mut filtered = self.empty_like();
+        filtered.pairs = self
+            .pairs
+            .iter()
+            .filter(|pair| f(&pair.key))
+            .cloned()
+            .collect();
+        filtered
+    }
+
+    /// Splits the builder into the pairs whose decoded key and value satisfy the predicate,
+    /// and the remaining pairs, preserving their order. Both builders retain the configuration.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_value("body.name", "Alice")
+    ///             .with_value("page", 2)
+    ///             .with_value("body.age", 30);
+    ///
+    /// let (body, query) = qs.partition(|key, _| key.starts_with("body."));
+    ///
+    /// assert_eq!(query.to_string(), "?q=apple&page=2");
+    /// assert_eq!(body.to_string(), "?body.name=Alice&body.age=30");
+    /// ```
+    pub fn partition<F: Fn(&str, &str) -> bool>(self, f: F) -> (QueryString, QueryString) {
+        let mut matching = self.empty_like();
+        let mut rest = self.empty_like();
+        for pair in self.pairs {
+            if f(&pair.key, pair.value()) {
+                matching.pairs.push(pair);
+            } else {
+                rest.pairs.push(pair);
+            }
+        }
+        (matching, rest)
+    }
+
+    /// Returns a copy of the builder in which the values of all pairs with one of the
+    /// specified keys are replaced with `***`, e.g. for logging. Bare flags are left as-is.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_value("api_key", "secret");
+    ///
+    /// assert_eq!(qs.redacted(&["api_key", "token"]).to_string(), "?q=apple&api_key=***");
+    /// assert_eq!(qs.to_string(), "?q=apple&api_key=secret");
+    /// ```
+    pub fn redacted(&self, keys: &[&str]) -> QueryString {
+        let mut redacted = self.clone();
+        for pair in &mut redacted.pairs {
+            if !pair.flag && keys.contains(&pair.key.as_str()) {
+                pair.set_value("***".to_string());
+            }
+        }
+        redacted
+    }
+
+    /// Collapses repeated `[]` suffixes of all keys into a single `[]`,
+    /// e.g. `tags[][]` becomes `tags[]`. If the builder enforces
+    /// [`unique_keys`](Self::unique_keys), keys that become equal are collapsed as well.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic()
+    ///             .with_array("tags[]", ["fruit", "red"]);
+    /// assert_eq!(qs.to_string(), "?tags[][]=fruit&tags[][]=red");
+    ///
+    /// qs.normalize_array_keys();
+    /// assert_eq!(qs.to_string(), "?tags[]=fruit&tags[]=red");
+    /// ```
+    pub fn normalize_array_keys(&mut self) {
+        for pair in &mut self.pairs {
+            while pair.key.ends_with("[][]") {
+                pair.key.truncate(pair.key.len() - 2);
+            }
+        }
+        self.collapse_duplicate_keys();
+    }
+
+    /// Reverses the order of the pairs in place.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic()
+    ///             .with_value("a", 1)
+    ///             .with_value("b", 2);
+    ///
+    /// qs.reverse();
+    ///
+    /// assert_eq!(qs.to_string(), "?b=2&a=1");
+    /// ```
+    pub fn reverse(&mut self) {
+        self.pairs.reverse();
+    }
+
+    /// Reverses the order of the pairs, consuming the builder.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("a", 1)
+    ///             .with_value("b", 2)
+    ///             .reversed();
+    ///
+    /// assert_eq!(qs.to_string(), "?b=2&a=1");
+    /// ```
+    pub fn reversed(mut self) -> Self {
+        self.reverse();
+        self
+    }
+
+    /// Removes consecutive pairs with the same decoded key and value, keeping the
+    /// first of each run. This mirrors [`Vec::dedup`].
+    ///
+    /// Only adjacent duplicates are removed in a single pass, so duplicates that are
+    /// separated by other pairs are kept. Sort the pairs first to remove all duplicates,
+    /// or use [`unique_keys`](Self::unique_keys) to keep only one pair per key.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic()
+    ///             .with_value("a", 1)
+    ///             .with_value("a", 1)
+    ///             .with_value("b", 2)
+    ///             .with_value("a", 1);
+    ///
+    /// qs.dedup_adjacent();
+    ///
+    /// assert_eq!(qs.to_string(), "?a=1&b=2&a=1");
+    /// ```
+    pub fn dedup_adjacent(&mut self) {
+        self.pairs
+            .dedup_by(|a, b| a.key == b.key && a.value() == b.value());
+    }
+
+    /// Swaps the pairs at the indices `a` and `b`. This mirrors [`slice::swap`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `a` or `b` are out of bounds.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic()
+    ///             .with_value("a", 1)
+    ///             .with_value("b", 2)
+    ///             .with_value("c", 3);
+    ///
+    /// qs.swap(0, 2);
+    ///
+    /// assert_eq!(qs.to_string(), "?c=3&b=2&a=1");
+    /// ```
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.pairs.swap(a, b);
+    }
+
+    /// Moves the first pair with the given key to the front, keeping the order
+    /// of all other pairs. Does nothing if the key is not present.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_value("page", 2)
+    ///             .with_value("route", "eu");
+    ///
+    /// qs.move_to_front("route");
+    ///
+    /// assert_eq!(qs.to_string(), "?route=eu&q=apple&page=2");
+    /// ```
+    pub fn move_to_front(&mut self, key: &str) {
+        if let Some(index) = self.pairs.iter().position(|pair| pair.key == key) {
+            self.pairs[..=index].rotate_right(1);
+        }
+    }
+
+    /// Splits the builder into two at the given index.
+    ///
+    /// The first `at` pairs remain in this builder, the returned builder contains the
+    /// remaining pairs and uses the same encoding. This mirrors [`Vec::split_off`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic()
+    ///             .with_value("a", 1)
+    ///             .with_value("b", 2)
+    ///             .with_value("c", 3);
+    ///
+    /// let rest = qs.split_off(1);
+    ///
+    /// assert_eq!(qs.to_string(), "?a=1");
+    /// assert_eq!(rest.to_string(), "?b=2&c=3");
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> QueryString {
+        let mut other = self.empty_like();
+        other.pairs = self.pairs.split_off(at);
+        other
+    }
+
+    /// Splits the builder into builders of at most `max_pairs` pairs each, preserving order.
+    /// Every builder uses the same configuration as this builder.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `max_pairs` is zero.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic();
+    /// qs.push_all("id", 1..=5);
+    ///
+    /// let chunks: Vec<String> = qs.chunks(2).map(|chunk| chunk.to_string()).collect();
+    /// assert_eq!(chunks, ["?id=1&id=2", "?id=3&id=4", "?id=5"]);
+    /// ```
+    pub fn chunks(&self, max_pairs: usize) -> impl Iterator<Item = QueryString> + '_ {
+        assert!(max_pairs > 0, "max_pairs must be greater than zero");
+        self.pairs.chunks(max_pairs).map(|pairs| {
+            let mut chunk = self.empty_like();
+            chunk.pairs = pairs.to_vec();
+            chunk
+        })
+    }
+
+    /// Splits the builder into builders whose rendered query string is at most `max_bytes`
+    /// long each, preserving order. Every builder uses the same configuration as this builder.
+    ///
+    /// A pair that does not fit into `max_bytes` on its own is returned in a separate builder
+    /// exceeding the limit.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic();
+    /// qs.push_all("id", [1, 22, 333, 4444]);
+    ///
+    /// let chunks: Vec<String> = qs.chunks_by_len(12).map(|chunk| chunk.to_string()).collect();
+    /// assert_eq!(chunks, ["?id=1&id=22", "?id=333", "?id=4444"]);
+    /// ```
+    pub fn chunks_by_len(&self, max_bytes: usize) -> impl Iterator<Item = QueryString> + '_ {
+        let prefix_len = self.prefix().len() + self.leading.as_ref().map_or(0, String::len);
+
+        let mut chunks = Vec::new();
+        let mut chunk = self.empty_like();
+        let mut chunk_len = prefix_len;
+        let mut rendered = false;
+
+        for pair in &self.pairs {
+            let pair_len = self.rendered_pair_len(pair);
+            if let Some(pair_len) = pair_len {
+                if rendered && chunk_len + self.options.separator.len() + pair_len > max_bytes {
+                    chunks.push(std::mem::replace(&mut chunk, self.empty_like()));
+                    chunk_len = prefix_len;
+                    rendered = false;
+                }
+
+                chunk_len += pair_len;
+                if rendered {
+                    chunk_len += self.options.separator.len();
+                }
+                rendered = true;
+            }
+            chunk.pairs.push(pair.clone());
+        }
+
+        if !chunk.pairs.is_empty() {
+            chunks.push(chunk);
+        }
+        chunks.into_iter()
+    }
+
+    /// Removes as many leading pairs as fit into a rendered query string of at most
+    /// `max_bytes`, and returns them in a builder with the same configuration.
+    ///
+    /// Calling this repeatedly until the builder is empty yields the same builders as
+    /// [`chunks_by_len`](Self::chunks_by_len). In particular, a leading pair that does not
+    /// fit into `max_bytes` on its own is returned alone, exceeding the limit.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let mut qs = QueryString::dynamic();
+    /// qs.push_all("id", [1, 22, 333, 4444]);
+    ///
+    /// let first = qs.take_within_len(12);
+    ///
+    /// assert_eq!(first.to_string(), "?id=1&id=22");
+    /// assert_eq!(qs.to_string(), "?id=333&id=4444");
+    /// ```
+    pub fn take_within_len(&mut self, max_bytes: usize) -> QueryString {
+        let mut len = self.prefix().len() + self.leading.as_ref().map_or(0, String::len);
+        let mut rendered = false;
+        let mut end = self.pairs.len();
+
+        for (i, pair) in self.pairs.iter().enumerate() {
+            if let Some(pair_len) = self.rendered_pair_len(pair) {
+                if rendered {
+                    if len + self.options.separator.len() + pair_len > max_bytes {
+                        end = i;
+                        break;
+                    }
+                    len += self.options.separator.len();
+                }
+                len += pair_len;
+                rendered = true;
+            }
+        }
+
+        let mut taken = self.empty_like();
+        taken.pairs = self.pairs.drain(..end).collect();
+        taken
+    }
+
+    /// Determines the rendered length of a single pair without any separators,
+    /// or `None` if the pair is not rendered.
+    fn rendered_pair_len(&self, pair: &Kvp) -> Option<usize> {
+        let mut single = self.empty_like();
+        single.leading = None;
+        single.leading_always = false;
+        single.pairs.push(pair.clone());
+
+        let mut counter = ByteCounter(0);
+        single
+            .write_encoded(&mut counter, "?")
+            .expect("counting bytes never fails");
+        counter.0.checked_sub(1)
+    }
+
+    /// Appends the pair unless the builder reached its maximum number of pairs.
+    fn push_pair(&mut self, pair: Kvp) -> bool {
+        if self.unique_keys {
+            if let Some(existing) = self.pairs.iter_mut().find(|p| p.key == pair.key) {
+                *existing = pair;
+                return true;
+            }
+        }
+
+        if self.max_pairs.is_some_and(|max| self.pairs.len() >= max) {
+            return false;
+        }
+
+        self.pairs.push(pair);
+        true
+    }
+
+    /// Keeps only the last value of each key at the position of its first occurrence,
+    /// if the builder enforces unique keys.
+    fn collapse_duplicate_keys(&mut self) {
+        if self.unique_keys {
+            for pair in std::mem::take(&mut self.pairs) {
+                self.push_pair(pair);
+            }
+        }
+    }
+
+    /// Drops the pairs exceeding the maximum number of pairs.
+    fn truncate_to_max_pairs(&mut self) {
+        if let Some(max) = self.max_pairs {
+            self.pairs.truncate(max);
+        }
+    }
+
+    /// Creates an empty builder using the same configuration as this builder.
+    fn empty_like(&self) -> Self {
+        Self {
+            pairs: Vec::default(),
+            value_transform: self.value_transform.clone(),
+            leading: self.leading.clone(),
+            allowed_literal: self.allowed_literal.clone(),
+            custom_encoder: self.custom_encoder.clone(),
+            ..*self
+        }
+    }
+
+    /// Renders the query string into the specified writer.
+    ///
+    /// This produces the same output as the [`Display`] implementation, but allows
+    /// streaming the query string into an existing buffer without an intermediate `String`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_value("category", "fruits and vegetables");
+    ///
+    /// let mut url = String::from("https://example.com/");
+    /// qs.write_into(&mut url).unwrap();
+    ///
+    /// assert_eq!(
+    ///     url,
+    ///     "https://example.com/?q=apple&category=fruits%20and%20vegetables"
+    /// );
+    /// ```
+    pub fn write_into<W: Write + ?Sized>(&self, w: &mut W) -> std::fmt::Result {
+        self.write_encoded(w, self.prefix())
+    }
+
+    /// Renders the query string into the specified writer using the pair separator, i.e. `&`,
+    /// instead of `?` as the leading separator, e.g. to append the pairs to a URL that already
+    /// has a query component.
+    ///
+    /// Nothing is written if the query string is empty.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("page", 2)
+    ///             .with_value("size", 20);
+    ///
+    /// let mut url = String::from("https://example.com/?q=apple");
+    /// qs.write_continuation(&mut url).unwrap();
+    ///
+    /// assert_eq!(url, "https://example.com/?q=apple&page=2&size=20");
+    /// ```
+    pub fn write_continuation<W: Write + ?Sized>(&self, w: &mut W) -> std::fmt::Result {
+        self.write_encoded(w, self.options.separator)
+    }
+
+    /// Gets the string written before the first pair.
+    fn prefix(&self) -> &'static str {
+        if self.options.include_prefix {
+            "?"
+        } else {
+            ""
+        }
+    }
+
+    /// Renders the encoded query string, starting with the specified separator.
+    fn write_encoded<W: Write + ?Sized>(&self, w: &mut W, start: &str) -> std::fmt::Result {
+        let encoder = self.encoder();
+        self.render(w, start, |w, part, encode| {
+            if encode {
+                encoder.write(w, part)
+            } else {
+                w.write_str(part)
+            }
+        })
+    }
+
+    /// Gets the effective encoder for keys and values.
+    fn encoder(&self) -> Encoder<'_> {
+        let mut encoder = Encoder::new(self.options.encoding);
+        encoder.allowed = &self.allowed_literal;
+        encoder.custom = self.custom_encoder.as_ref().map(|custom| &*custom.0);
+        encoder.lowercase = self.options.lowercase_encoding;
+        if self.options.encode_path_chars {
+            encoder.extra.extend_from_slice(b"/?:@");
+        }
+        if let Some(enabled) = self.options.space_as_plus {
+            encoder.space_as_plus = enabled;
+        }
+        if encoder.space_as_plus {
+            encoder.extra.push(b'+');
+        }
+        if self.options.kv_delimiter.is_ascii() {
+            encoder.extra.push(self.options.kv_delimiter as u8);
+        }
+        encoder
+            .extra
+            .extend(self.options.separator.bytes().filter(|b| b.is_ascii()));
+        encoder
+    }
+
+    /// Renders the pairs using the specified function to write keys and values.
+    /// The function is told whether the key or value should be encoded.
+    fn render<W, F>(&self, w: &mut W, start: &str, write_part: F) -> std::fmt::Result
+    where
+        W: Write + ?Sized,
+        F: FnMut(&mut W, &str, bool) -> std::fmt::Result,
+    {
+        if self.options.grouped {
+            self.render_pairs(w, start, self.grouped_pairs().into_iter(), write_part)
+        } else {
+            self.render_pairs(w, start, self.pairs.iter(), write_part)
+        }
+    }
+
+    /// Orders the pairs by the first occurrence of their key, keeping the order within each key.
+    fn grouped_pairs(&self) -> Vec<&Kvp> {
+        let mut groups: Vec<Vec<&Kvp>> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for pair in &self.pairs {
+            match index.entry(pair.key.as_str()) {
+                hash_map::Entry::Occupied(entry) => groups[*entry.get()].push(pair),
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(groups.len());
+                    groups.push(vec![pair]);
+                }
+            }
+        }
+        groups.into_iter().flatten().collect()
+    }
+
+    fn render_pairs<'a, W, I, F>(
+        &self,
+        w: &mut W,
+        start: &str,
+        pairs: I,
+        mut write_part: F,
+    ) -> std::fmt::Result
+    where
+        W: Write + ?Sized,
+        I: Iterator<Item = &'a Kvp>,
+        F: FnMut(&mut W, &str, bool) -> std::fmt::Result,
+    {
+        let mut first = true;
+        for pair in pairs {
+            let value = match &self.value_transform {
+                Some(transform) if !pair.flag => Cow::Owned(transform.apply(pair.value())),
+                _ => Cow::Borrowed(pair.value()),
+            };
+
+            if self.options.skip_empty_values && !pair.flag && value.is_empty() {
+                continue;
+            }
+
+            if first {
+                w.write_str(start)?;
+                w.write_str(self.leading.as_deref().unwrap_or_default())?;
+                first = false;
+            } else {
+                w.write_str(self.options.separator)?;
+            }
+
+            write_part(w, &pair.key, !pair.is_key_encoded())?;
+            let bare = self.options.empty_policy == EmptyPolicy::BareKey && value.is_empty();
+            if !pair.flag && !bare {
+                w.write_char(self.options.kv_delimiter)?;
+                write_part(w, &value, !pair.raw && !pair.value_encoded)?;
+            }
+        }
+
+        if first && self.leading_always {
+            w.write_str(start)?;
+            w.write_str(self.leading.as_deref().unwrap_or_default())?;
+        }
+        Ok(())
+    }
+}
+
+/// Builders are compared by their decoded key-value pairs in stored order, i.e.
+/// the pairs are not canonicalized. The encoding and other rendering options
+/// are not considered, and bare flags compare equal to pairs with an empty value.
+///
+/// ## Example
+///
+/// ```
+/// use std::collections::BTreeSet;
+/// use query_string_builder::QueryString;
+///
+/// let a = QueryString::dynamic().with_value("a", 1).with_value("b", 2);
+/// let b = QueryString::dynamic().with_value("b", 2).with_value("a", 1);
+///
+/// assert_ne!(a, b);
+/// assert!(a < b);
+///
+/// let variants = BTreeSet::from([a.clone(), b, a]);
+/// assert_eq!(variants.len(), 2);
+/// ```
+impl PartialEq for QueryString {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for QueryString {}
+
+impl PartialOrd for QueryString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueryString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl PartialEq<str> for QueryString {
+    /// Compares the rendered query string to the specified string.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("a", 1)
+    ///             .with_value("b", 2);
+    ///
+    /// assert_eq!(qs, "?a=1&b=2");
+    /// ```
+    fn eq(&self, other: &str) -> bool {
+        let mut remaining = PrefixMatcher(other);
+        self.write_into(&mut remaining).is_ok() && remaining.0.is_empty()
+    }
+}
+
+/// A writer that counts the number of bytes written.
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+/// A writer that consumes its string for as long as the written chunks match,
+/// allowing for comparisons without rendering into a buffer.
+struct PrefixMatcher<'a>(&'a str);
+
+impl Write for PrefixMatcher<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        match self.0.strip_prefix(s) {
+            Some(rest) => {
+                self.0 = rest;
+                Ok(())
+            }
+            None => Err(std::fmt::Error),
+        }
+    }
+}
+
+impl PartialEq<&str> for QueryString {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl IntoIterator for QueryString {
+    type Item = (String, String);
+    type IntoIter = IntoIter;
+
+    /// Consumes the builder, yielding the decoded key-value pairs in insertion order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic().with_value("q", "apple");
+    /// let pairs: Vec<(String, String)> = qs.into_iter().collect();
+    ///
+    /// assert_eq!(pairs, [("q".to_string(), "apple".to_string())]);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.pairs.into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a QueryString {
+    type Item = (&'a str, &'a str);
+    type IntoIter = Iter<'a>;
+
+    /// Yields the decoded key-value pairs in insertion order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_value("page", 2);
+    ///
+    /// for (key, value) in &qs {
+    ///     assert!(!key.is_empty() && !value.is_empty());
+    /// }
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: ToString + Ord, V: ToString> From<BTreeMap<K, V>> for QueryString {
+    /// Creates a query string builder from the map, adding the pairs in key order.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use query_string_builder::QueryString;
+    ///
+    /// let map = BTreeMap::from([("q", "apple"), ("category", "fruits")]);
+    /// let qs = QueryString::from(map);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?category=fruits&q=apple"
+    /// );
+    /// ```
+    fn from(map: BTreeMap<K, V>) -> Self {
+        let mut qs = QueryString::dynamic();
+        for (key, value) in map {
+            qs.push(key, value);
+        }
+        qs
+    }
+}
+
+impl Default for QueryString {
+    /// Creates a new, empty query string builder, see [`QueryString::dynamic`].
+    fn default() -> Self {
+        QueryString::dynamic()
+    }
+}
+
+impl Display for QueryString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.write_into(f)
+    }
+}
+
+/// A transformation applied to values at render time.
+#[derive(Clone)]
+struct ValueTransform(Arc<dyn Fn(&str) -> String + Send + Sync>);
+
+impl ValueTransform {
+    fn apply(&self, value: &str) -> String {
+        (self.0)(value)
+    }
+}
+
+impl Debug for ValueTransform {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ValueTransform")
+    }
+}
+
+#[derive(Clone)]
+struct CustomEncoder(Arc<dyn ValueEncoder>);
+
+impl Debug for CustomEncoder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CustomEncoder")
+    }
+}
+
+/// A value that is formatted at most once, when it is first rendered or looked up.
+///
+/// Clones share the value and its formatted string, so it is formatted at most once
+/// across all clones.
+#[derive(Clone)]
+struct LazyValue(Arc<LazyCell>);
+
+struct LazyCell {
+    display: Box<dyn Display + Send + Sync>,
+    formatted: OnceLock<String>,
+}
+
+impl LazyValue {
+    fn new(display: Box<dyn Display + Send + Sync>) -> Self {
+        Self(Arc::new(LazyCell {
+            display,
+            formatted: OnceLock::new(),
+        }))
+    }
+
+    fn get(&self) -> &str {
+        self.0.formatted.get_or_init(|| self.0.display.to_string())
+    }
+
+    fn into_string(self) -> String {
+        match Arc::try_unwrap(self.0) {
+            Ok(cell) => {
+                let display = cell.display;
+                cell.formatted
+                    .into_inner()
+                    .unwrap_or_else(|| display.to_string())
+            }
+            Err(shared) => LazyValue(shared).get().to_string(),
+        }
+    }
+}
+
+impl Debug for LazyValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LazyValue")
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Kvp {
+    key: String,
+    value: String,
+    /// Indicates a bare key that is rendered without the `=` sign.
+    flag: bool,
+    /// Indicates that the key and value are already percent encoded.
+    raw: bool,
+    /// Indicates that only the value is already percent encoded.
+    value_encoded: bool,
+    /// Indicates that only the key is already percent encoded.
+    key_encoded: bool,
+    /// A value that is formatted on first use, replacing `value`.
+    lazy: Option<LazyValue>,
+}
+
+impl Kvp {
+    fn new(key: String, value: String) -> Self {
+        Self {
+            key,
+            value,
+            flag: false,
+            raw: false,
+            value_encoded: false,
+            key_encoded: false,
+            lazy: None,
+        }
+    }
+
+    fn flag(key: String) -> Self {
+        Self {
+            key,
+            value: String::new(),
+            flag: true,
+            raw: false,
+            value_encoded: false,
+            key_encoded: false,
+            lazy: None,
+        }
+    }
+
+    fn raw(key: String, value: String) -> Self {
+        Self {
+            raw: true,
+            ..Self::new(key, value)
+        }
+    }
+
+    fn value_encoded(key: String, value: String) -> Self {
+        Self {
+            value_encoded: true,
+            ..Self::new(key, value)
+        }
+    }
+
+    fn key_encoded(key: String, value: String) -> Self {
+        Self {
+            key_encoded: true,
+            ..Self::new(key, value)
+        }
+    }
+
+    /// Determines whether the key is stored percent encoded.
+    fn is_key_encoded(&self) -> bool {
+        self.raw || self.key_encoded
+    }
+
+    fn lazy(key: String, value: Box<dyn Display + Send + Sync>) -> Self {
+        Self {
+            lazy: Some(LazyValue::new(value)),
+            ..Self::new(key, String::new())
+        }
+    }
+
+    /// Gets the decoded value, formatting a lazy value if needed.
+    fn value(&self) -> &str {
+        match &self.lazy {
+            Some(lazy) => lazy.get(),
+            None => &self.value,
+        }
+    }
+
+    /// Gets a mutable reference to the decoded value, formatting a lazy value if needed.
+    fn value_mut(&mut self) -> &mut String {
+        if let Some(lazy) = self.lazy.take() {
+            self.value = lazy.into_string();
+        }
+        &mut self.value
+    }
+
+    /// Gets the key, percent decoding it if it is stored encoded.
+    fn decoded_key(&self) -> Cow<'_, str> {
+        if self.is_key_encoded() {
+            percent_decode_str(&self.key).decode_utf8_lossy()
+        } else {
+            Cow::Borrowed(&self.key)
+        }
+    }
+
+    /// Gets the value, percent decoding it if it is stored encoded.
+    fn decoded_value(&self) -> Cow<'_, str> {
+        if self.raw || self.value_encoded {
+            percent_decode_str(self.value()).decode_utf8_lossy()
+        } else {
+            Cow::Borrowed(self.value())
+        }
+    }
+
+    /// Replaces the key with a decoded key. The value is kept as stored.
+    fn set_key(&mut self, key: String) {
+        if self.raw {
+            self.raw = false;
+            self.value_encoded = !self.flag;
+        }
+        self.key_encoded = false;
+        self.key = key;
+    }
+
+    /// Replaces the value with a decoded value, which is encoded again when rendering.
+    fn set_value(&mut self, value: String) {
+        if self.raw {
+            self.key = self.decoded_key().into_owned();
+            self.raw = false;
+        }
+        self.value_encoded = false;
+        self.lazy = None;
+        self.value = value;
+    }
+
+    /// Gets a mutable reference to the decoded value, percent decoding a stored encoded
+    /// key and value first so that changes are encoded again when rendering.
+    fn decoded_value_mut(&mut self) -> &mut String {
+        if self.raw || self.value_encoded {
+            let value = self.decoded_value().into_owned();
+            self.set_value(value);
+        }
+        self.value_mut()
+    }
+
+    fn as_tuple(&self) -> (&str, &str) {
+        (&self.key, self.value())
+    }
+
+    fn into_tuple(mut self) -> (String, String) {
+        self.value_mut();
+        (self.key, self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        let qs = QueryStringSimple::default();
+        assert_eq!(qs.to_string(), "");
+        assert_eq!(qs.len(), 0);
+        assert!(qs.is_empty());
+    }
+
+    #[test]
+    fn test_simple() {
+        let qs = QueryString::dynamic()
+            .with_value("q", "apple???")
+            .with_value("category", "fruits and vegetables")
+            .with_value("tasty", true)
+            .with_value("weight", 99.9);
+        assert_eq!(
+            qs.to_string(),
+            "?q=apple???&category=fruits%20and%20vegetables&tasty=true&weight=99.9"
+        );
+        assert_eq!(qs.len(), 4);
+        assert!(!qs.is_empty());
+    }
+
+    #[test]
+    fn test_encoding() {
+        let qs = QueryString::dynamic()
+            .with_value("q", "Grünkohl")
+            .with_value("category", "Gemüse");
+        assert_eq!(qs.to_string(), "?q=Gr%C3%BCnkohl&category=Gem%C3%BCse");
+    }
+
+    #[test]
+    fn test_emoji() {
+        let qs = QueryString::dynamic()
+            .with_value("q", "🥦")
+            .with_value("🍽️", "🍔🍕");
+        assert_eq!(
+            qs.to_string(),
+            "?q=%F0%9F%A5%A6&%F0%9F%8D%BD%EF%B8%8F=%F0%9F%8D%94%F0%9F%8D%95"
+        );
+    }
+
+    #[test]
+    fn test_optional() {
+        let qs = QueryString::dynamic()
+            .with_value("q", "celery")
+            .with_opt_value("taste", None::<String>)
+            .with_opt_value("category", Some("fruits and vegetables"))
+            .with_opt_value("tasty", Some(true))
+            .with_opt_value("weight", Some(99.9));
+        assert_eq!(
+            qs.to_string(),
+            "?q=celery&category=fruits%20and%20vegetables&tasty=true&weight=99.9"
+        );
+        assert_eq!(qs.len(), 4); // not five!
+    }
+
+    #[test]
+    fn test_unique_keys() {
+        let mut qs = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_value("b", 2)
+            .with_value("a", 3)
+            .unique_keys();
+        assert_eq!(qs.to_string(), "?a=3&b=2");
+
+        qs.push("b", 4);
+        qs.push_all("c", [5, 6]);
+        assert_eq!(qs.to_string(), "?a=3&b=4&c=6");
+
+        qs.append(
+            QueryString::dynamic()
+                .with_value("d", 7)
+                .with_value("a", 8)
+                .with_value("d", 9),
+        );
+        assert_eq!(qs.to_string(), "?a=8&b=4&c=6&d=9");
+
+        qs.merge(
+            QueryString::dynamic()
+                .with_value("b", 10)
+                .with_value("b", 11),
+        );
+        assert_eq!(qs.to_string(), "?a=8&b=11&c=6&d=9");
+
+        let mut qs = qs.with_max_pairs(4);
+        assert!(qs.push_within_limit("a", 12));
+        assert!(!qs.push_within_limit("e", 13));
+        assert_eq!(qs.to_string(), "?a=12&b=11&c=6&d=9");
+    }
+
+    #[test]
+    fn test_unique_keys_after_key_rewrites() {
+        let mut qs = QueryString::dynamic()
+            .unique_keys()
+            .with_value("a", 1)
+            .with_value("b", 2);
+        qs.map_keys(|_| "k".into());
+        assert_eq!(qs.to_string(), "?k=2");
+
+        let mut qs = QueryString::dynamic()
+            .unique_keys()
+            .with_value("a[]", 1)
+            .with_value("a[][]", 2)
+            .with_value("b", 3);
+        qs.normalize_array_keys();
+        assert_eq!(qs.to_string(), "?a[]=2&b=3");
+    }
+
+    #[test]
+    fn test_max_pairs() {
+        let mut qs = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_value("b", 2)
+            .with_max_pairs(3)
+            .with_flag("c")
+            .with_value("d", 4);
+        assert_eq!(qs.to_string(), "?a=1&b=2&c");
+
+        qs.push("e", 5);
+        assert!(qs.try_push("f", 6).is_ok());
+        assert!(!qs.push_if_absent("g", 7));
+        assert!(!qs.push_within_limit("h", 8));
+        assert_eq!(qs.len(), 3);
+
+        qs.merge(
+            QueryString::dynamic()
+                .with_value("a", 9)
+                .with_value("a", 10),
+        );
+        assert_eq!(qs.to_string(), "?a=9&a=10&b=2");
+
+        let qs = qs.append_into(QueryString::dynamic().with_value("i", 11));
+        assert_eq!(qs.len(), 3);
+
+        let mut qs = QueryString::dynamic().with_value("a", 1).with_max_pairs(0);
+        assert!(!qs.push_within_limit("b", 2));
+        assert_eq!(qs.to_string(), "?a=1");
+    }
+
+    #[test]
+    fn test_grouped() {
+        let qs = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_value("b", 2)
+            .with_flag("c")
+            .with_value("a", 3)
+            .with_value("c", 4)
+            .with_value("b", 5);
+        assert_eq!(qs.to_string(), "?a=1&b=2&c&a=3&c=4&b=5");
+
+        let qs = qs.grouped();
+        assert_eq!(qs.to_string(), "?a=1&a=3&b=2&b=5&c&c=4");
+        assert_eq!(qs.to_decoded_string(), "?a=1&a=3&b=2&b=5&c&c=4");
+        assert_eq!(qs.nth(1), Some(("b", "2")));
+        assert_eq!(QueryString::dynamic().grouped().to_string(), "");
+    }
+
+    #[test]
+    fn test_empty_value_policy() {
+        let qs = QueryString::dynamic()
+            .with_value("a", "")
+            .with_flag("b")
+            .with_value("c", "x");
+        assert_eq!(qs.to_string(), "?a=&b&c=x");
+
+        let qs = qs.empty_value_policy(EmptyPolicy::BareKey);
+        assert_eq!(qs.to_string(), "?a&b&c=x");
+
+        let qs = qs.with_value_transform(|v| v.replace('x', ""));
+        assert_eq!(qs.to_string(), "?a&b&c");
+
+        let qs = qs.skip_empty_values();
+        assert_eq!(qs.to_string(), "?b");
+    }
+
+    #[test]
+    fn test_char_and_bytes() {
+        let qs = QueryString::form()
+            .with_char("a b", ' ')
+            .with_bytes("c d", b"e f+")
+            .with_bytes("g", &[0xC3, 0xBC, 0xC3, 0x80, 0x80])
+            .with_bytes("h", &[])
+            .with_bytes("i", &[b'a', 0xF0, 0x9F]);
+        assert_eq!(qs.get("a b"), Some(" "));
+        assert_eq!(qs.get("c d"), Some("e+f%2B"));
+        assert_eq!(
+            qs.to_string(),
+            "?a+b=+&c+d=e+f%2B&g=%C3%BC%C3%80%80&h=&i=a%F0%9F"
+        );
+    }
+
+    #[test]
+    fn test_value_raw() {
+        let qs = QueryString::dynamic()
+            .with_value("a", "%20")
+            .with_value_raw("b%5B%5D", "%20")
+            .with_value("c", "%20");
+
+        assert_eq!(qs.get("b%5B%5D"), Some("%20"));
+        assert_eq!(qs.to_string(), "?a=%2520&b%5B%5D=%20&c=%2520");
+        assert_eq!(qs.to_decoded_string(), "?a=%20&b%5B%5D=%20&c=%20");
+
+        let qs = QueryString::dynamic()
+            .with_kv_delimiter(':')
+            .with_value_raw("a:", ":");
+        assert_eq!(qs.to_string(), "?a:::");
+    }
+
+    #[test]
+    fn test_value_preencoded() {
+        let qs = QueryString::form()
+            .with_value("a b", "%20")
+            .with_value_preencoded("a b", "%20+")
+            .with_value_preencoded("c", "");
+
+        assert_eq!(qs.as_pairs(), [("a b", "%20"), ("a b", "%20+"), ("c", "")]);
+        assert_eq!(qs.to_string(), "?a+b=%2520&a+b=%20+&c=");
+    }
+
+    #[test]
+    fn test_value_or_empty() {
+        let qs = QueryString::dynamic()
+            .with_value_or_empty("a", Some(1))
+            .with_value_or_empty("b", None::<u8>)
+            .with_value_or_empty("c", Some(""));
+        assert_eq!(qs.len(), 3);
+        assert_eq!(qs.get("b"), Some(""));
+        assert_eq!(qs.to_string(), "?a=1&b=&c=");
+    }
+
+    #[test]
+    fn test_opt_values() {
+        let qs = QueryString::dynamic()
+            .with_value("a", 0)
+            .with_opt_values([("b", Some(1)), ("c", None), ("b", Some(2))])
+            .with_opt_values(Vec::<(&str, Option<u8>)>::new())
+            .with_opt_values([("d", None::<u8>)]);
+        assert_eq!(qs.to_string(), "?a=0&b=1&b=2");
+    }
+
+    #[test]
+    fn test_display() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Counting(Arc<AtomicUsize>);
+
+        impl Display for Counting {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                let count = self.0.fetch_add(1, Ordering::Relaxed) + 1;
+                write!(f, "call {count}")
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut qs = QueryString::dynamic()
+            .with_display('k', Counting(calls.clone()))
+            .with_value("x", 1);
+        assert_eq!(calls.load(Ordering::Relaxed), 0);
+
+        let copy = qs.clone();
+        assert_eq!(qs.to_string(), "?k=call%201&x=1");
+        assert_eq!(qs.get("k"), Some("call 1"));
+        assert_eq!(copy.get("k"), Some("call 1"));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        match qs.entry("k") {
+            Entry::Occupied(mut entry) => entry.get_mut().push('!'),
+            Entry::Vacant(_) => unreachable!(),
+        }
+        assert_eq!(qs.to_vec()[0], ("k".to_string(), "call 1!".to_string()));
+        assert_eq!(copy.get("k"), Some("call 1"));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        let qs = QueryString::dynamic().with_display('k', Counting(calls.clone()));
+        assert_eq!(qs.clone().to_vec()[0].1, "call 2");
+        assert_eq!(qs.get("k"), Some("call 2"));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_value_checked() {
+        let qs = QueryString::dynamic()
+            .with_value_checked("a", "🍎", 4)
+            .unwrap();
+
+        let err = qs.with_value_checked("b", "🍎", 3).unwrap_err();
+        assert_eq!(err.key(), "b");
+        assert_eq!(err.value(), "🍎");
+        assert_eq!((err.actual(), err.max()), (4, 3));
+        assert_eq!(
+            err.to_string(),
+            "value length of 4 bytes for key \"b\" exceeds the maximum of 3 bytes"
+        );
+        assert_eq!(err.into_builder().to_string(), "?a=%F0%9F%8D%8E");
+    }
+
+    #[test]
+    fn test_value_display() {
+        let qs = QueryString::dynamic()
+            .with_value_display("a b", &1.5)
+            .with_value_display("c", &'&')
+            .with_value_display("d", &"")
+            .with_opt_value_display("e", None)
+            .with_opt_value_display("f", Some(&2u8));
+        assert_eq!(qs.get("a b"), Some("1.5"));
+        assert_eq!(qs.to_string(), "?a%20b=1.5&c=%26&d=&f=2");
+    }
+
+    #[test]
+    fn test_flag() {
+        let qs = QueryString::dynamic()
+            .with_flag("verbose")
+            .with_value("q", "apple")
+            .with_flag("🍽️");
+
+        assert_eq!(qs.len(), 3);
+        assert_eq!(qs.get("verbose"), Some(""));
+        assert_eq!(qs.to_string(), "?verbose&q=apple&%F0%9F%8D%BD%EF%B8%8F");
+        assert_eq!(qs.to_decoded_string(), "?verbose&q=apple&🍽️");
+    }
+
+    #[test]
+    fn test_flag_opt() {
+        let qs = QueryString::dynamic()
+            .with_flag_opt("a b", true)
+            .with_flag_opt("c", false)
+            .with_value("d", 1);
+        assert_eq!(qs.len(), 2);
+        assert_eq!(qs.to_string(), "?a%20b&d=1");
+    }
+
+    #[test]
+    fn test_skip_empty_values() {
+        let qs = QueryString::dynamic()
+            .with_value("a", "")
+            .with_value("b", 2)
+            .with_value("c", "")
+            .with_flag("d")
+            .skip_empty_values();
+
+        assert_eq!(qs.len(), 4);
+        assert_eq!(qs.to_string(), "?b=2&d");
+        assert_eq!(qs.to_decoded_string(), "?b=2&d");
+
+        let qs = QueryString::dynamic()
+            .skip_empty_values()
+            .with_value("a", "");
+        assert_eq!(qs.len(), 1);
+        assert!(!qs.is_empty());
+        assert_eq!(qs.to_string(), "");
+
+        let qs = QueryString::dynamic().with_value("a", "");
+        assert_eq!(qs.to_string(), "?a=");
+    }
+
+    #[test]
+    fn test_kv_delimiter() {
+        let qs = QueryString::dynamic()
+            .with_kv_delimiter(':')
+            .with_value("a:b", "c=d")
+            .with_flag("e");
+        assert_eq!(qs.to_string(), "?a%3Ab:c%3Dd&e");
+        assert_eq!(qs.to_decoded_string(), "?a:b:c=d&e");
+
+        // Characters already encoded by the set are not encoded twice.
+        let qs = QueryString::dynamic()
+            .with_kv_delimiter('#')
+            .with_value("a", "#");
+        assert_eq!(qs.to_string(), "?a#%23");
+
+        let qs = QueryString::dynamic()
+            .with_kv_delimiter('→')
+            .with_value("a", "→");
+        assert_eq!(qs.to_string(), "?a→%E2%86%92");
+    }
+
+    #[test]
+    fn test_with_encoder() {
+        struct Reverse;
+
+        impl ValueEncoder for Reverse {
+            fn encode<'a>(&self, s: &'a str) -> Cow<'a, str> {
+                Cow::Owned(s.chars().rev().collect())
+            }
+        }
+
+        let mut qs = QueryString::dynamic()
+            .allow_literal(&['b'])
+            .with_encoder(Box::new(Reverse))
+            .with_value("key", "a b")
+            .with_value_raw("raw", "a%20b")
+            .with_flag("flag");
+        assert_eq!(qs.to_string(), "?yek=b a&raw=a%20b&galf");
+        assert_eq!(qs.split_off(1).to_string(), "?raw=a%20b&galf");
+
+        let qs = QueryString::rfc3986().with_encoder(Box::new(Encoding::Form));
+        assert_eq!(qs.with_value("a+b", "c d").to_string(), "?a%2Bb=c+d");
+    }
+
+    #[test]
+    fn test_value_transform() {
+        let qs = QueryString::dynamic()
+            .with_value("q", " 🍎 apple ")
+            .with_value(" key ", "  ")
+            .with_flag(" flag ")
+            .with_value_transform(|value| value.trim().to_string());
+
+        assert_eq!(qs.get("q"), Some(" 🍎 apple "));
+        assert_eq!(
+            qs.to_string(),
+            "?q=%F0%9F%8D%8E%20apple&%20key%20=&%20flag%20"
+        );
+        assert_eq!(qs.to_decoded_string(), "?q=🍎 apple& key =& flag ");
+
+        // Empty values are determined after the transformation.
+        let qs = qs.skip_empty_values();
+        assert_eq!(qs.to_string(), "?q=%F0%9F%8D%8E%20apple&%20flag%20");
+
+        // The transformation is retained when splitting.
+        let mut qs = qs;
+        let rest = qs.split_off(1);
+        assert_eq!(rest.to_string(), "?%20flag%20");
+        assert!(format!("{qs:?}").contains("ValueTransform"));
+    }
+
+    #[test]
+    fn test_space_as_plus() {
+        for qs in [
+            QueryString::dynamic(),
+            QueryString::rfc3986(),
+            QueryString::form(),
+        ] {
+            let qs = qs.with_value("a b", "c+d");
+            assert!(qs
+                .clone()
+                .space_as_plus(true)
+                .to_string()
+                .starts_with("?a+b=c%2Bd"));
+            assert!(qs.space_as_plus(false).to_string().starts_with("?a%20b=c"));
+        }
+
+        let qs = QueryString::rfc3986()
+            .space_as_plus(false)
+            .with_value("+", " ");
+        assert_eq!(qs.to_string(), "?+=%20");
+    }
+
+    #[test]
+    fn test_lowercase_encoding() {
+        let qs = QueryString::dynamic()
+            .lowercase_encoding()
+            .with_value("ÄB", "<C>")
+            .with_value_raw("%C3%84", "%3C")
+            .with_bytes("b", &[0xAB]);
+        assert_eq!(qs.to_string(), "?%c3%84B=%3cC%3e&%C3%84=%3C&b=%ab");
+        assert_eq!(qs.rendered_len(), qs.to_string().len());
+    }
+
+    #[test]
+    fn test_encode_path_chars() {
+        let value = "/a?b:c@d";
+        for qs in [QueryString::dynamic(), QueryString::rfc3986()] {
+            let qs = qs.encode_path_chars().with_value(value, value);
+            assert_eq!(qs.to_string(), "?%2Fa%3Fb%3Ac%40d=%2Fa%3Fb%3Ac%40d");
+        }
+
+        let qs = QueryString::dynamic()
+            .encode_path_chars()
+            .allow_literal(&['/'])
+            .with_value("a", value)
+            .with_value_raw("b", value);
+        assert_eq!(qs.to_string(), "?a=/a%3Fb%3Ac%40d&b=/a?b:c@d");
+        assert_eq!(qs.rendered_len(), qs.to_string().len());
+
+        let qs = QueryString::dynamic().with_value("a", value);
+        assert_eq!(qs.to_string(), "?a=/a?b:c@d");
+    }
+
+    #[test]
+    fn test_allow_literal() {
+        let qs = QueryString::dynamic()
+            .allow_literal(&['ü'])
+            .allow_literal(&['%', ' '])
+            .with_value("Grünkohl", "100 %")
+            .with_value("b", "+#");
+        assert_eq!(qs.to_string(), "?Grünkohl=100 %&b=%2B%23");
+
+        let qs = QueryString::dynamic()
+            .with_kv_delimiter(':')
+            .allow_literal(&[':'])
+            .with_value("a", "10:30");
+        assert_eq!(qs.to_string(), "?a:10:30");
+    }
+
+    #[test]
+    fn test_leading() {
+        let qs = QueryString::dynamic()
+            .with_leading("v2 ;")
+            .with_value("a", "")
+            .with_value("b", "🍎");
+        assert_eq!(qs.to_string(), "?v2 ;a=&b=%F0%9F%8D%8E");
+        assert_eq!(qs.to_decoded_string(), "?v2 ;a=&b=🍎");
+
+        // The leading string is not rendered if all pairs are skipped.
+        let qs = QueryString::dynamic()
+            .with_leading("v2;")
+            .skip_empty_values()
+            .with_value("a", "");
+        assert_eq!(qs.to_string(), "");
+
+        let qs = qs.with_leading_always("v2;");
+        assert_eq!(qs.to_string(), "?v2;");
+
+        let qs = qs.with_value("b", 2);
+        assert_eq!(qs.to_string(), "?v2;b=2");
+
+        let qs = qs.with_leading("v3;");
+        assert_eq!(qs.to_string(), "?v3;b=2");
+    }
+
+    #[test]
+    fn test_namespaced() {
+        let qs = QueryString::dynamic()
+            .with_namespaced("filter", "status", "open")
+            .with_namespaced("filter", "🍽️", "a&b")
+            .with_namespaced("", 1, 2);
+        assert_eq!(
+            qs.to_string(),
+            "?filter[status]=open&filter[%F0%9F%8D%BD%EF%B8%8F]=a%26b&[1]=2"
+        );
+    }
+
+    #[test]
+    fn test_push_optional() {
+        let mut qs = QueryString::dynamic();
+        qs.push("a", "apple");
+        qs.push_opt("b", None::<String>);
+        qs.push_opt("c", Some("🍎 apple"));
+
+        assert_eq!(
+            format!("https://example.com/{qs}"),
+            "https://example.com/?a=apple&c=%F0%9F%8D%8E%20apple"
+        );
+    }
+
+    #[test]
+    fn test_try_push() {
+        let mut qs = QueryString::dynamic();
+        qs.try_push("q", "a&b=c").unwrap();
+
+        let err = qs.try_push("key=", "value").unwrap_err();
+        assert_eq!(err.key(), "key=");
+        assert_eq!(err.character(), '=');
+        assert_eq!(err.position(), 3);
+
+        let err = qs.try_push("ü\n", "value").unwrap_err();
+        assert_eq!(err.character(), '\n');
+        assert_eq!(err.position(), 2);
+        assert_eq!(
+            err.to_string(),
+            "invalid character '\\n' at position 2 in key \"ü\\n\""
+        );
+
+        assert!(qs.try_push("#", "value").is_err());
+
+        assert_eq!(qs.len(), 1);
+        assert_eq!(qs.to_string(), "?q=a%26b%3Dc");
+    }
+
+    #[test]
+    fn test_push_key_fmt() {
+        let mut qs = QueryString::dynamic();
+        let prefix = "filter";
+        for i in 0..2 {
+            qs.push_key_fmt(format_args!("{prefix}[{i}]"), i * 10);
+        }
+        qs.push_key_fmt(format_args!("a&b"), "c");
+
+        assert_eq!(qs.get("filter[1]"), Some("10"));
+        assert_eq!(qs.to_string(), "?filter[0]=0&filter[1]=10&a%26b=c");
+    }
+
+    #[test]
+    fn test_push_if_absent() {
+        let mut qs = QueryString::dynamic().with_flag("verbose");
+
+        assert!(qs.push_if_absent("page", 1));
+        assert!(!qs.push_if_absent("page", 2));
+        assert!(!qs.push_if_absent("verbose", true));
+        assert!(qs.push_if_absent("Page", 3));
+
+        assert_eq!(qs.to_string(), "?verbose&page=1&Page=3");
+    }
+
+    #[test]
+    fn test_push_all() {
+        let mut qs = QueryString::dynamic();
+        qs.push("q", "apple");
+        qs.push_all("id", vec![1, 2, 3]);
+        qs.push_all("empty", Vec::<String>::new());
+
+        assert_eq!(qs.len(), 4);
+        assert_eq!(
+            format!("https://example.com/{qs}"),
+            "https://example.com/?q=apple&id=1&id=2&id=3"
+        );
+    }
+
+    #[test]
+    fn test_pairs_from() {
+        let source = QueryString::dynamic().with_value("a b", 1).with_flag("c");
+        let qs = QueryString::dynamic()
+            .with_value("z", 0)
+            .with_pairs_from(&source)
+            .with_pairs_from([("d", "&")]);
+        assert_eq!(qs.to_string(), "?z=0&a%20b=1&c=&d=%26");
+        assert_eq!(source.to_string(), "?a%20b=1&c");
+    }
+
+    #[test]
+    fn test_multimap() {
+        let qs = QueryString::dynamic()
+            .with_value("b", 2)
+            .with_flag("a")
+            .with_value("b", 1)
+            .with_value("a b", 3);
+
+        let map = qs.to_multimap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map["a"], [""]);
+        assert_eq!(map["b"], ["2", "1"]);
+        assert_eq!(map["a b"], ["3"]);
+
+        let sorted: Vec<_> = qs.to_sorted_multimap().into_iter().collect();
+        assert_eq!(
+            sorted,
+            [
+                ("a".to_string(), vec![String::new()]),
+                ("a b".to_string(), vec!["3".to_string()]),
+                ("b".to_string(), vec!["2".to_string(), "1".to_string()]),
+            ]
+        );
+        assert!(QueryString::dynamic().to_multimap().is_empty());
+    }
+
+    #[test]
+    fn test_append() {
+        let qs = QueryString::dynamic().with_value("q", "apple");
+        let more = QueryString::dynamic().with_value("q", "pear");
+
+        let mut qs = qs.append_into(more);
+        qs.append(QueryString::dynamic().with_value("answer", "42"));
+
+        assert_eq!(
+            format!("https://example.com/{qs}"),
+            "https://example.com/?q=apple&q=pear&answer=42"
+        );
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut qs = QueryString::dynamic();
+        assert_eq!(qs.capacity(), 0);
+        qs.push("a", 1);
+        qs.push("b", 2);
+        assert!(qs.capacity() >= 2);
+
+        let _ = qs.split_off(0);
+        qs.shrink_to_fit();
+        assert_eq!(qs.capacity(), 0);
+        assert!(qs.is_empty());
+    }
+
+    #[test]
+    fn test_iter() {
+        let qs = QueryString::dynamic()
+            .with_value("q", "🍎 apple")
+            .with_value("tasty", true)
+            .with_value("weight", 99.9);
+
+        let mut iter = qs.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(("q", "🍎 apple")));
+        assert_eq!(iter.next_back(), Some(("weight", "99.9")));
+        assert_eq!(iter.next(), Some(("tasty", "true")));
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(
+            qs.as_pairs(),
+            [("q", "🍎 apple"), ("tasty", "true"), ("weight", "99.9")]
+        );
+        assert!(QueryString::dynamic().as_pairs().is_empty());
+
+        assert_eq!(
+            qs.to_vec(),
+            [
+                ("q".to_string(), "🍎 apple".to_string()),
+                ("tasty".to_string(), "true".to_string()),
+                ("weight".to_string(), "99.9".to_string())
+            ]
+        );
+        assert!(QueryString::dynamic().to_vec().is_empty());
+    }
+
+    #[test]
+    fn test_keys_and_values() {
+        let qs = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_flag("b")
+            .with_display("c", 3)
+            .with_value("a", 4);
+
+        assert_eq!(qs.keys().collect::<Vec<_>>(), ["a", "b", "c", "a"]);
+        assert_eq!(qs.values().collect::<Vec<_>>(), ["1", "", "3", "4"]);
+
+        let qs = QueryString::dynamic();
+        assert_eq!(qs.keys().count(), 0);
+        assert_eq!(qs.values().count(), 0);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut qs = QueryString::form()
+            .with_value("a", 1)
+            .with_flag("b")
+            .with_display("c", 3);
+
+        let mut drain = qs.drain();
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next_back(), Some(("c".to_string(), "3".to_string())));
+        assert_eq!(drain.next(), Some(("a".to_string(), "1".to_string())));
+        drop(drain);
+        assert!(qs.is_empty());
+
+        qs.push("d", 4);
+        let pairs: Vec<_> = qs.drain().collect();
+        assert_eq!(pairs, [("d".to_string(), "4".to_string())]);
+        assert!(qs.is_empty());
+        assert_eq!(qs.encoding(), Encoding::Form);
+    }
+
+    #[test]
+    fn test_lookup() {
+        let qs = QueryString::dynamic()
+            .with_value("Page", 1)
+            .with_value("page", 2)
+            .with_value("Grün", "kohl");
+
+        assert_eq!(qs.get("page"), Some("2"));
+        assert_eq!(qs.get("PAGE"), None);
+        assert!(qs.contains_key("Page"));
+        assert!(!qs.contains_key("size"));
+
+        assert_eq!(qs.get_ignore_case("PAGE"), Some("1"));
+        assert_eq!(qs.get_ignore_case("grün"), Some("kohl"));
+        assert_eq!(qs.get_ignore_case("GRÜN"), None); // ASCII only
+        assert!(qs.contains_key_ignore_case("pAgE"));
+        assert!(!qs.contains_key_ignore_case("size"));
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let qs = QueryString::dynamic()
+            .with_value("q", "🍎 apple")
+            .with_value("tasty", true);
+
+        let mut keys = Vec::new();
+        for (key, _) in &qs {
+            keys.push(key);
+        }
+        assert_eq!(keys, ["q", "tasty"]);
+
+        let mut iter = qs.into_iter();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(
+            iter.next_back(),
+            Some(("tasty".to_string(), "true".to_string()))
+        );
+        assert_eq!(iter.next(), Some(("q".to_string(), "🍎 apple".to_string())));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_get_parsed() {
+        let qs = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_value("a", "x")
+            .with_value("b", "true")
+            .with_flag("c");
+        assert_eq!(qs.get_parsed::<u8>("a"), Some(Ok(1)));
+        assert_eq!(qs.get_parsed::<bool>("b"), Some(Ok(true)));
+        assert_eq!(qs.get_parsed::<String>("c"), Some(Ok(String::new())));
+        assert!(qs.get_parsed::<u8>("c").unwrap().is_err());
+        assert_eq!(qs.get_parsed::<u8>("d"), None);
+    }
+
+    #[test]
+    fn test_get_bytes() {
+        let qs = QueryString::dynamic()
+            .with_bytes("a", &[0xFF, 0x00, b'%'])
+            .with_value("b", "%FF")
+            .with_value_preencoded("c", "%FF%20")
+            .with_raw_fragment_verbatim("d=%FF+&e")
+            .with_value("a", "ignored");
+
+        assert_eq!(qs.get_bytes("a"), Some(vec![0xFF, 0x00, b'%']));
+        assert_eq!(qs.get_bytes("b"), Some(b"%FF".to_vec()));
+        assert_eq!(qs.get_bytes("c"), Some(vec![0xFF, b' ']));
+        assert_eq!(qs.get_bytes("d"), Some(vec![0xFF, b'+']));
+        assert_eq!(qs.get_bytes("e"), Some(Vec::new()));
+        assert_eq!(qs.get_bytes("f"), None);
+    }
+
+    #[test]
+    fn test_count_key() {
+        let qs = QueryString::dynamic()
+            .with_value("a b", 1)
+            .with_flag("a b")
+            .with_value("A b", 2)
+            .with_value("a b", "");
+        assert_eq!(qs.count_key("a b"), 3);
+        assert_eq!(qs.count_key("a%20b"), 0);
+        assert_eq!(QueryString::dynamic().count_key("a b"), 0);
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut qs = QueryString::dynamic()
+            .with_value("page", 3)
+            .with_value("page", 4);
+
+        assert_eq!(
+            qs.get_or_insert_with("page", || -> u8 { unreachable!() }),
+            "3"
+        );
+        assert_eq!(qs.get_or_insert_with("size", || 20), "20");
+        assert_eq!(qs.get_or_insert_with("size", || 50), "20");
+
+        assert_eq!(qs.len(), 3);
+        assert_eq!(qs.to_string(), "?page=3&page=4&size=20");
+    }
+
+    #[test]
+    fn test_positional_access() {
+        let qs = QueryString::dynamic()
+            .with_value("q", "🍎 apple")
+            .with_value("tasty", true)
+            .with_value("weight", 99.9);
+
+        assert_eq!(qs.first(), Some(("q", "🍎 apple")));
+        assert_eq!(qs.last(), Some(("weight", "99.9")));
+        assert_eq!(qs.nth(1), Some(("tasty", "true")));
+        assert_eq!(qs.nth(3), None);
+
+        let qs = QueryString::dynamic();
+        assert_eq!(qs.first(), None);
+        assert_eq!(qs.last(), None);
+        assert_eq!(qs.nth(0), None);
+    }
+
+    #[test]
+    fn test_eq_ignoring() {
+        let a = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_value("ts", 10)
+            .with_value("b", 2)
+            .with_value("nonce", "x");
+        let b = QueryString::form()
+            .with_value("nonce", "y")
+            .with_value("a", 1)
+            .with_value("b", 2);
+
+        assert!(a.eq_ignoring(&b, &["ts", "nonce"]));
+        assert!(!a.eq_ignoring(&b, &["nonce"]));
+        assert!(!a.eq_ignoring(&b, &[]));
+
+        let reordered = QueryString::dynamic().with_value("b", 2).with_value("a", 1);
+        assert!(!a.eq_ignoring(&reordered, &["ts", "nonce"]));
+        assert!(a.eq_ignoring(&reordered, &["a", "b", "ts", "nonce"]));
+    }
+
+    #[test]
+    fn test_is_sorted() {
+        assert!(QueryString::dynamic().is_sorted());
+        let qs = QueryString::dynamic()
+            .with_value("a", "")
+            .with_flag("a")
+            .with_value("a", "b")
+            .with_value("a", "b");
+        assert!(qs.is_sorted());
+        assert!(!qs.clone().with_value("A", "c").is_sorted());
+        assert!(qs.with_value("ab", "").is_sorted());
+    }
+
+    #[test]
+    fn test_to_canonical() {
+        let qs = QueryString::form()
+            .with_value("b", "2")
+            .with_value("a", "z")
+            .with_value("a", "y")
+            .with_value("A", "!*'()")
+            .with_value_raw("c%2fd", "%e4+")
+            .with_bytes("e", &[0xFF])
+            .with_value("_", "")
+            .skip_empty_values()
+            .with_value_transform(|value| value.to_uppercase());
+        assert_eq!(
+            qs.to_canonical(),
+            "A=%21%2A%27%28%29&a=Y&a=Z&b=2&c%2Fd=%E4%2B&e=%FF"
         );
-        assert_eq!(qs.len(), 4);
-        assert!(!qs.is_empty());
+        assert_eq!(QueryString::dynamic().to_canonical(), "");
     }
 
     #[test]
-    fn test_encoding() {
+    fn test_rendered_len() {
+        let qs = QueryString::form()
+            .with_leading("v=1&")
+            .with_value("🍎", "a b")
+            .with_flag("c")
+            .with_value("d", "")
+            .with_value_raw("e%20", "f");
+        assert_eq!(qs.rendered_len(), qs.to_string().len());
+        assert_eq!(qs.rendered_len(), 33);
+        assert_eq!(
+            QueryString::cookie()
+                .with_value("a", 1)
+                .with_value("b", 2)
+                .rendered_len(),
+            8
+        );
+    }
+
+    #[test]
+    fn test_try_to_string() {
+        let qs = QueryString::dynamic().with_value("q", "🍎");
+        assert_eq!(qs.try_to_string(15).unwrap(), "?q=%F0%9F%8D%8E");
+
+        let err = qs.try_to_string(14).unwrap_err();
+        assert_eq!(err, LengthExceeded::new(15, 14));
+        assert_eq!(
+            err.to_string(),
+            "query string length of 15 bytes exceeds the maximum of 14 bytes"
+        );
+
+        assert_eq!(QueryString::dynamic().try_to_string(0).unwrap(), "");
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut qs = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_value("b", 2)
+            .with_value("a", 3)
+            .with_value("c", 4);
+        let other = QueryString::dynamic()
+            .with_value("d", 5)
+            .with_value("a", 6)
+            .with_value("a", 7)
+            .with_value("c", 8);
+
+        qs.merge(other);
+
+        assert_eq!(qs.len(), 5);
+        assert_eq!(qs.to_string(), "?a=6&a=7&b=2&c=8&d=5");
+    }
+
+    #[test]
+    fn test_repeated() {
         let qs = QueryString::dynamic()
-            .with_value("q", "Grünkohl")
-            .with_value("category", "Gemüse");
-        assert_eq!(qs.to_string(), "?q=Gr%C3%BCnkohl&category=Gem%C3%BCse");
+            .with_repeated("x y", "1&", 3)
+            .with_repeated("z", 2, 0);
+        assert_eq!(qs.len(), 3);
+        assert_eq!(qs.to_string(), "?x%20y=1%26&x%20y=1%26&x%20y=1%26");
+
+        let qs = QueryString::dynamic()
+            .with_max_pairs(2)
+            .with_repeated("x", 1, usize::MAX);
+        assert_eq!(qs.to_string(), "?x=1&x=1");
+
+        let qs = QueryString::dynamic()
+            .unique_keys()
+            .with_value("x", 0)
+            .with_repeated("x", 1, usize::MAX);
+        assert_eq!(qs.to_string(), "?x=1");
     }
 
     #[test]
-    fn test_emoji() {
+    fn test_ip() {
+        let v6: IpAddr = "2001:db8::1".parse().unwrap();
+        let v4: IpAddr = "192.168.0.1".parse().unwrap();
+
         let qs = QueryString::dynamic()
-            .with_value("q", "🥦")
-            .with_value("🍽️", "🍔🍕");
+            .with_ip("a", v6, true)
+            .with_ip("b", v6, false)
+            .with_ip("c", v4, true);
+        assert_eq!(qs.get("a"), Some("[2001:db8::1]"));
         assert_eq!(
             qs.to_string(),
-            "?q=%F0%9F%A5%A6&%F0%9F%8D%BD%EF%B8%8F=%F0%9F%8D%94%F0%9F%8D%95"
+            "?a=[2001:db8::1]&b=2001:db8::1&c=192.168.0.1"
         );
+
+        let qs = QueryString::rfc3986().with_ip("a", v6, true);
+        assert_eq!(qs.to_string(), "?a=%5B2001:db8::1%5D");
     }
 
     #[test]
-    fn test_optional() {
+    fn test_array() {
         let qs = QueryString::dynamic()
-            .with_value("q", "celery")
-            .with_opt_value("taste", None::<String>)
-            .with_opt_value("category", Some("fruits and vegetables"))
-            .with_opt_value("tasty", Some(true))
-            .with_opt_value("weight", Some(99.9));
+            .with_array("tags", ["fruit", "red apple"])
+            .with_array("empty", Vec::<String>::new())
+            .with_array("ids", 1..=2);
+
+        assert_eq!(qs.len(), 4);
+        assert_eq!(qs.get("tags[]"), Some("fruit"));
         assert_eq!(
             qs.to_string(),
-            "?q=celery&category=fruits%20and%20vegetables&tasty=true&weight=99.9"
+            "?tags[]=fruit&tags[]=red%20apple&ids[]=1&ids[]=2"
         );
-        assert_eq!(qs.len(), 4); // not five!
     }
 
     #[test]
-    fn test_push_optional() {
+    fn test_opt_array() {
+        let qs = QueryString::dynamic()
+            .with_opt_array("a", Some(vec![1, 2]))
+            .with_opt_array("b", None::<Vec<u8>>)
+            .with_opt_array("c", Some(std::iter::empty::<&str>()))
+            .with_opt_array("d", Some(["x y"]));
+        assert_eq!(qs.len(), 3);
+        assert_eq!(qs.to_string(), "?a[]=1&a[]=2&d[]=x%20y");
+    }
+
+    #[test]
+    fn test_indexed_array() {
+        let qs = QueryString::dynamic()
+            .with_indexed_array("a", ["x", "y z"])
+            .with_indexed_array("a", ["w"])
+            .with_indexed_array("b", Vec::<String>::new());
+
+        assert_eq!(qs.len(), 3);
+        assert_eq!(qs.get("a%5B1%5D"), Some("y z"));
+        assert_eq!(qs.to_string(), "?a%5B0%5D=x&a%5B1%5D=y%20z&a%5B0%5D=w");
+
+        let qs = QueryString::form()
+            .lowercase_encoding()
+            .with_indexed_array("ü b", ["ä c"]);
+        assert_eq!(qs.to_string(), "?%c3%bc+b%5b0%5d=%c3%a4+c");
+    }
+
+    #[test]
+    fn test_normalize_array_keys() {
+        let mut qs = QueryString::dynamic()
+            .with_value("a[][][]", 1)
+            .with_value("b[]", 2)
+            .with_value("c[][]d", 3)
+            .with_value("[][]", 4)
+            .with_flag("e[][]");
+
+        qs.normalize_array_keys();
+        assert_eq!(qs.to_string(), "?a[]=1&b[]=2&c[][]d=3&[]=4&e[]");
+    }
+
+    #[test]
+    fn test_map_values_and_keys() {
+        let mut qs = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_flag("b")
+            .with_value("c", "");
+
+        let mut calls = 0;
+        qs.map_values(|value| {
+            calls += 1;
+            format!("{value}!")
+        });
+        assert_eq!(calls, 2);
+        assert_eq!(qs.to_string(), "?a=1!&b&c=!");
+
+        qs.map_keys(|key| key.repeat(2));
+        assert_eq!(qs.to_string(), "?aa=1!&bb&cc=!");
+    }
+
+    #[test]
+    fn test_map_values_and_keys_reencode() {
+        let mut qs = QueryString::dynamic()
+            .with_bytes("a", b"x y")
+            .with_value_raw("b%20c", "1%2B1")
+            .with_value_preencoded("d", "%26");
+
+        let mut seen = Vec::new();
+        qs.map_values(|value| {
+            seen.push(value.to_string());
+            "p&q=r".into()
+        });
+        assert_eq!(seen, ["x y", "1+1", "&"]);
+        assert_eq!(qs.to_string(), "?a=p%26q%3Dr&b%20c=p%26q%3Dr&d=p%26q%3Dr");
+
+        let mut qs = QueryString::dynamic()
+            .with_value_raw("b%20c", "1%2B1")
+            .with_raw_fragment_verbatim("e%26f");
+
+        qs.map_keys(|key| format!("{key}&x=y"));
+        assert_eq!(qs.get("b c&x=y"), Some("1%2B1"));
+        assert_eq!(qs.to_string(), "?b%20c%26x%3Dy=1%2B1&e%26f%26x%3Dy");
+    }
+
+    #[test]
+    fn test_remove_empty_keys() {
+        let mut qs = QueryString::dynamic()
+            .with_value("", 1)
+            .with_value(" ", 2)
+            .with_flag("")
+            .with_value_raw("", 3);
+        assert_eq!(qs.remove_empty_keys(), 3);
+        assert_eq!(qs.remove_empty_keys(), 0);
+        assert_eq!(qs.to_string(), "?%20=2");
+    }
+
+    #[test]
+    fn test_prefix_keys() {
+        let qs = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_flag("b")
+            .with_value_raw("c%20d", "e")
+            .prefix_keys("x y.");
+
+        assert_eq!(qs.get("x y.a"), Some("1"));
+        assert!(qs.contains_key("x y.b"));
+        assert_eq!(qs.to_string(), "?x%20y.a=1&x%20y.b&x%20y.c%20d=e");
+        assert_eq!(
+            QueryString::dynamic().prefix_keys("p"),
+            QueryString::dynamic()
+        );
+    }
+
+    #[test]
+    fn test_filter_keys() {
+        let qs = QueryString::form()
+            .with_value("a b", 1)
+            .with_flag("c")
+            .with_value("a b", 2);
+
+        let filtered = qs.filter_keys(|key| key.starts_with('a'));
+        assert_eq!(filtered.to_string(), "?a+b=1&a+b=2");
+        assert_eq!(filtered.encoding(), Encoding::Form);
+        assert!(qs.filter_keys(|_| false).is_empty());
+        assert_eq!(qs.filter_keys(|_| true), qs);
+    }
+
+    #[test]
+    fn test_partition() {
+        let qs = QueryString::form()
+            .with_value("a", 1)
+            .with_flag("b")
+            .with_value("c", 22)
+            .with_value("d", 3);
+
+        let (long, short) = qs
+            .clone()
+            .partition(|key, value| key == "b" || value.len() > 1);
+        assert_eq!(long.to_string(), "?b&c=22");
+        assert_eq!(short.to_string(), "?a=1&d=3");
+        assert_eq!(long.encoding(), Encoding::Form);
+        assert_eq!(short.encoding(), Encoding::Form);
+
+        let (all, none) = qs.clone().partition(|_, _| true);
+        assert_eq!(all, qs);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_redacted() {
+        let qs = QueryString::rfc3986()
+            .with_value("token", "a+b")
+            .with_value("q", "1+1")
+            .with_flag("token")
+            .with_value("Token", "c")
+            .with_value("token", "");
+
+        let redacted = qs.redacted(&["token", "api_key"]);
+        assert_eq!(
+            redacted.to_string(),
+            "?token=***&q=1+1&token&Token=c&token=***"
+        );
+        assert_eq!(qs.to_string(), "?token=a+b&q=1+1&token&Token=c&token=");
+        assert_eq!(qs.redacted(&[]), qs);
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut qs = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_flag("b")
+            .with_value("c", 3);
+
+        qs.reverse();
+        assert_eq!(qs.to_string(), "?c=3&b&a=1");
+
+        let qs = qs.reversed();
+        assert_eq!(qs.to_string(), "?a=1&b&c=3");
+
         let mut qs = QueryString::dynamic();
-        qs.push("a", "apple");
-        qs.push_opt("b", None::<String>);
-        qs.push_opt("c", Some("🍎 apple"));
+        qs.reverse();
+        assert!(qs.is_empty());
+    }
+
+    #[test]
+    fn test_dedup_adjacent() {
+        let mut qs = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_value("a", 1)
+            .with_value("a", 2)
+            .with_value("b", 2)
+            .with_value("b", 2)
+            .with_value("b", 2)
+            .with_value("a", 1);
 
+        qs.dedup_adjacent();
+        assert_eq!(qs.to_string(), "?a=1&a=2&b=2&a=1");
+
+        let mut qs = QueryString::dynamic();
+        qs.dedup_adjacent();
+        assert!(qs.is_empty());
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut qs = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_flag("b")
+            .with_value("c", 3);
+
+        qs.swap(0, 1);
+        assert_eq!(qs.to_string(), "?b&a=1&c=3");
+
+        qs.swap(2, 2);
+        assert_eq!(qs.to_string(), "?b&a=1&c=3");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swap_out_of_bounds() {
+        QueryString::dynamic().with_value("a", 1).swap(0, 1);
+    }
+
+    #[test]
+    fn test_move_to_front() {
+        let mut qs = QueryString::dynamic()
+            .with_value("a", 1)
+            .with_value("b", 2)
+            .with_value("c", 3)
+            .with_value("c", 4);
+
+        qs.move_to_front("c");
+        assert_eq!(qs.to_string(), "?c=3&a=1&b=2&c=4");
+
+        qs.move_to_front("c");
+        assert_eq!(qs.to_string(), "?c=3&a=1&b=2&c=4");
+
+        qs.move_to_front("missing");
+        assert_eq!(qs.to_string(), "?c=3&a=1&b=2&c=4");
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut qs = QueryString::rfc3986()
+            .with_value("a", "1+1")
+            .with_value("b", "2+2")
+            .with_value("c", "3+3");
+
+        let rest = qs.split_off(1);
+        assert_eq!(qs.to_string(), "?a=1+1");
+        assert_eq!(rest.to_string(), "?b=2+2&c=3+3");
+
+        let empty = qs.split_off(1);
+        assert!(empty.is_empty());
+        assert_eq!(qs.len(), 1);
+
+        let all = qs.split_off(0);
+        assert!(qs.is_empty());
+        assert_eq!(all.len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_off_out_of_bounds() {
+        QueryString::dynamic().with_value("a", 1).split_off(2);
+    }
+
+    #[test]
+    fn test_chunks() {
+        let mut qs = QueryString::rfc3986();
+        qs.push_all("id", ["1+1", "2", "3"]);
+
+        let chunks: Vec<_> = qs.chunks(2).collect();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0], "?id=1+1&id=2");
+        assert_eq!(chunks[1], "?id=3");
+        assert_eq!(chunks[0].encoding(), Encoding::Rfc3986);
+
+        assert_eq!(qs.chunks(3).count(), 1);
+        assert_eq!(QueryString::dynamic().chunks(3).count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_chunks_zero() {
+        let _ = QueryString::dynamic().chunks(0);
+    }
+
+    #[test]
+    fn test_chunks_by_len() {
+        let qs = QueryString::dynamic()
+            .with_leading("v2;")
+            .skip_empty_values()
+            .with_value("a", "🍎")
+            .with_value("empty", "")
+            .with_value("b", 2)
+            .with_value("c", 3)
+            .with_value("toolong", "abcdefghijklmnop")
+            .with_value("d", 4);
+
+        for max_bytes in [0, 10, 20, 24, 40, 100] {
+            let chunks: Vec<_> = qs.chunks_by_len(max_bytes).collect();
+            assert_eq!(chunks.iter().map(QueryString::len).sum::<usize>(), qs.len());
+            for chunk in &chunks {
+                let rendered = chunk.to_string();
+                let rendered_pairs = chunk.iter().filter(|(_, v)| !v.is_empty()).count();
+                assert!(rendered.len() <= max_bytes || rendered_pairs == 1);
+            }
+        }
+
+        let chunks: Vec<String> = qs.chunks_by_len(24).map(|c| c.to_string()).collect();
         assert_eq!(
-            format!("https://example.com/{qs}"),
-            "https://example.com/?a=apple&c=%F0%9F%8D%8E%20apple"
+            chunks,
+            [
+                "?v2;a=%F0%9F%8D%8E&b=2",
+                "?v2;c=3",
+                "?v2;toolong=abcdefghijklmnop",
+                "?v2;d=4"
+            ]
         );
+
+        assert_eq!(QueryString::dynamic().chunks_by_len(10).count(), 0);
     }
 
     #[test]
-    fn test_append() {
-        let qs = QueryString::dynamic().with_value("q", "apple");
-        let more = QueryString::dynamic().with_value("q", "pear");
+    fn test_take_within_len() {
+        let qs = QueryString::dynamic()
+            .with_leading("v2;")
+            .skip_empty_values()
+            .with_value("a", "🍎")
+            .with_value("empty", "")
+            .with_value("b", 2)
+            .with_value("c", 3)
+            .with_value("toolong", "abcdefghijklmnop")
+            .with_value("d", 4);
 
-        let mut qs = qs.append_into(more);
-        qs.append(QueryString::dynamic().with_value("answer", "42"));
+        for max_bytes in [0, 10, 20, 24, 40, 100] {
+            let mut rest = qs.clone();
+            let mut taken = Vec::new();
+            while !rest.is_empty() {
+                taken.push(rest.take_within_len(max_bytes));
+            }
+            assert_eq!(taken, qs.chunks_by_len(max_bytes).collect::<Vec<_>>());
+        }
 
+        let mut rest = qs.clone();
         assert_eq!(
-            format!("https://example.com/{qs}"),
-            "https://example.com/?q=apple&q=pear&answer=42"
+            rest.take_within_len(24).to_string(),
+            "?v2;a=%F0%9F%8D%8E&b=2"
+        );
+        assert_eq!(rest.len(), 3);
+
+        let mut empty = QueryString::dynamic();
+        assert!(empty.take_within_len(10).is_empty());
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        let qs = QueryString::dynamic()
+            .with_value("q", "🍎 & 🍐")
+            .with_flag("x");
+        assert_eq!(qs.to_bytes(), qs.to_string().as_bytes());
+        assert!(qs.to_bytes().is_ascii());
+        assert!(QueryString::dynamic().to_bytes().is_empty());
+    }
+
+    #[test]
+    fn test_as_fragment() {
+        let qs = QueryString::dynamic()
+            .with_value("a b", "#1?")
+            .with_flag("c");
+        assert_eq!(qs.as_fragment(), "#a%20b=%231?&c");
+        assert_eq!(qs.to_string(), "?a%20b=%231?&c");
+
+        let qs = QueryString::cookie().with_value("a", 1).with_value("b", 2);
+        assert_eq!(qs.as_fragment(), "#a=1; b=2");
+
+        assert_eq!(QueryString::dynamic().as_fragment(), "");
+    }
+
+    #[test]
+    fn test_to_decoded_string() {
+        let qs = QueryString::dynamic()
+            .with_value("q", "Grünkohl")
+            .with_value("🍽️", "a=b&c");
+        assert_eq!(qs.to_decoded_string(), "?q=Grünkohl&🍽️=a=b&c");
+        assert_eq!(QueryString::dynamic().to_decoded_string(), "");
+    }
+
+    #[test]
+    fn test_write_continuation() {
+        let mut url = String::from("/?a=1");
+        QueryString::dynamic().write_continuation(&mut url).unwrap();
+        assert_eq!(url, "/?a=1");
+
+        QueryString::dynamic()
+            .with_leading("v=2&")
+            .with_value("b c", "d")
+            .with_flag("e")
+            .write_continuation(&mut url)
+            .unwrap();
+        assert_eq!(url, "/?a=1&v=2&b%20c=d&e");
+    }
+
+    #[test]
+    fn test_write_into() {
+        let qs = QueryString::dynamic()
+            .with_value("q", "🍎 apple")
+            .with_value("tasty", true);
+
+        let mut buffer = String::from("https://example.com/");
+        qs.write_into(&mut buffer).unwrap();
+        assert_eq!(buffer, format!("https://example.com/{qs}"));
+
+        let mut buffer = String::new();
+        QueryString::dynamic().write_into(&mut buffer).unwrap();
+        assert_eq!(buffer, "");
+    }
+
+    #[test]
+    fn test_macro() {
+        const KEY: &str = "key";
+        let color: Option<&str> = None;
+
+        let qs = crate::query_string! {
+            "q" => "🍎 apple",
+            KEY => 42,
+            ("we".to_string() + "ight") => 99.9,
+            "color" ?=> color,
+            "tasty" ?=> Some(true)
+        };
+
+        assert_eq!(qs.len(), 4);
+        assert_eq!(
+            qs.to_string(),
+            "?q=%F0%9F%8D%8E%20apple&key=42&weight=99.9&tasty=true"
+        );
+
+        let qs = crate::query_string! {};
+        assert!(qs.is_empty());
+    }
+
+    #[test]
+    fn test_cookie() {
+        let cookie = QueryString::cookie()
+            .with_value("a", "b;c")
+            .with_value("d", "e f")
+            .with_flag("g");
+        assert_eq!(cookie.to_string(), "a=b%3Bc; d=e%20f; g");
+        assert_eq!(cookie.to_decoded_string(), "a=b;c; d=e f; g");
+        assert_eq!(QueryString::cookie().to_string(), "");
+
+        let mut header = String::from("x=1");
+        cookie.write_continuation(&mut header).unwrap();
+        assert_eq!(header, "x=1; a=b%3Bc; d=e%20f; g");
+
+        let chunks: Vec<_> = cookie.chunks_by_len(16).map(|c| c.to_string()).collect();
+        assert_eq!(chunks, ["a=b%3Bc; d=e%20f", "g"]);
+    }
+
+    #[test]
+    fn test_default() {
+        #[derive(Default)]
+        struct Config {
+            query: QueryString,
+        }
+
+        let config = Config::default();
+        assert!(config.query.is_empty());
+        assert_eq!(config.query.encoding(), Encoding::Query);
+        assert_eq!(QueryString::default(), QueryString::dynamic());
+    }
+
+    #[test]
+    fn test_rfc3986() {
+        let qs = QueryString::rfc3986()
+            .with_value("q", "1+1 = 2")
+            .with_value("tag", "#math")
+            .with_value("a&b", "50%")
+            .with_value("chars", "!$'()*,;:@/?-._~")
+            .with_value("unsafe", "\"<>[]\\^`{|}");
+        assert_eq!(qs.encoding(), Encoding::Rfc3986);
+        assert_eq!(
+            qs.to_string(),
+            "?q=1+1%20%3D%202&tag=%23math&a%26b=50%25&chars=!$'()*,;:@/?-._~\
+             &unsafe=%22%3C%3E%5B%5D%5C%5E%60%7B%7C%7D"
         );
+
+        let mut qs = QueryString::dynamic().with_value("q", "1+1");
+        assert_eq!(qs.encoding(), Encoding::Query);
+        assert_eq!(qs.to_string(), "?q=1%2B1");
+
+        qs.set_encoding(Encoding::Rfc3986);
+        assert_eq!(qs.encoding(), Encoding::Rfc3986);
+        assert_eq!(qs.to_string(), "?q=1+1");
+        assert_eq!(qs.len(), 1);
+    }
+
+    #[test]
+    fn test_ord() {
+        let a = QueryString::dynamic().with_value("a", 1);
+        let ab = QueryString::dynamic().with_value("a", 1).with_value("b", 2);
+        let b = QueryString::rfc3986().with_value("b", 0);
+
+        assert_eq!(a, QueryString::form().with_value("a", 1));
+        assert_eq!(QueryString::dynamic(), QueryString::dynamic());
+        assert_ne!(a, ab);
+
+        assert!(QueryString::dynamic() < a);
+        assert!(a < ab);
+        assert!(ab < b);
+        assert_eq!(a.cmp(&a.clone()), Ordering::Equal);
+
+        let mut sorted = vec![b.clone(), ab.clone(), a.clone()];
+        sorted.sort();
+        assert_eq!(sorted, [a, ab, b]);
+    }
+
+    #[test]
+    fn test_eq_str() {
+        let qs = QueryString::dynamic()
+            .with_value("q", "🍎 apple")
+            .with_flag("tasty");
+
+        assert_eq!(qs, "?q=%F0%9F%8D%8E%20apple&tasty");
+        assert!(qs == *"?q=%F0%9F%8D%8E%20apple&tasty");
+        assert_ne!(qs, "?q=🍎 apple&tasty");
+        assert_ne!(qs, "?q=%F0%9F%8D%8E%20apple");
+        assert_ne!(qs, "?q=%F0%9F%8D%8E%20apple&tasty&");
+        assert_eq!(QueryString::dynamic(), "");
+    }
+
+    #[test]
+    fn test_from_btree_map() {
+        let mut map = BTreeMap::new();
+        map.insert("weight".to_string(), 99.9);
+        map.insert("amount".to_string(), 3.0);
+        map.insert("price".to_string(), 1.5);
+
+        let qs = QueryString::from(map);
+        assert_eq!(qs.to_string(), "?amount=3&price=1.5&weight=99.9");
+
+        let qs = QueryString::from(BTreeMap::<String, String>::new());
+        assert!(qs.is_empty());
     }
 
     #[test]
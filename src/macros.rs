@@ -0,0 +1,40 @@
+/// Constructs a dynamic [`QueryString`](crate::QueryString) from a list of key-value pairs.
+///
+/// Pairs separated with `=>` are appended using [`with_value`](crate::QueryString::with_value),
+/// pairs separated with `?=>` take an `Option` and are appended using
+/// [`with_opt_value`](crate::QueryString::with_opt_value).
+///
+/// Keys must be a single token tree, i.e. a literal, an identifier or a parenthesized expression.
+///
+/// ## Example
+///
+/// ```
+/// use query_string_builder::query_string;
+///
+/// let qs = query_string! {
+///     "q" => "apple",
+///     "tasty" => true,
+///     "color" ?=> None::<String>,
+///     "category" ?=> Some("fruits and vegetables"),
+/// };
+///
+/// assert_eq!(
+///     format!("https://example.com/{qs}"),
+///     "https://example.com/?q=apple&tasty=true&category=fruits%20and%20vegetables"
+/// );
+/// ```
+#[macro_export]
+macro_rules! query_string {
+    (@munch $qs:expr;) => {
+        $qs
+    };
+    (@munch $qs:expr; $key:tt ?=> $value:expr $(, $($rest:tt)*)?) => {
+        $crate::query_string!(@munch $qs.with_opt_value($key, $value); $($($rest)*)?)
+    };
+    (@munch $qs:expr; $key:tt => $value:expr $(, $($rest:tt)*)?) => {
+        $crate::query_string!(@munch $qs.with_value($key, $value); $($($rest)*)?)
+    };
+    ($($body:tt)*) => {
+        $crate::query_string!(@munch $crate::QueryString::dynamic(); $($body)*)
+    };
+}
@@ -0,0 +1,160 @@
+use crate::{EmptyPolicy, Encoding};
+
+/// The rendering options of a [`QueryString`](crate::QueryString), see
+/// [`QueryString::with_options`](crate::QueryString::with_options).
+///
+/// The default options render a regular query string such as `?a=1&b=2`
+/// using [`Encoding::Query`].
+///
+/// Settings that hold data, such as the string set using
+/// [`with_leading`](crate::QueryString::with_leading), the characters set using
+/// [`allow_literal`](crate::QueryString::allow_literal) or a custom value encoder, as well as
+/// settings that limit which pairs are stored, such as
+/// [`with_max_pairs`](crate::QueryString::with_max_pairs) and
+/// [`unique_keys`](crate::QueryString::unique_keys), are not part of the options, which keeps
+/// them cheap to copy.
+///
+/// ## Example
+///
+/// ```
+/// use query_string_builder::{QueryString, QueryStringOptions};
+///
+/// let options = QueryStringOptions {
+///     include_prefix: false,
+///     ..QueryStringOptions::default()
+/// };
+///
+/// let qs = QueryString::with_options(options).with_value("q", "apple");
+/// assert_eq!(qs.to_string(), "q=apple");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QueryStringOptions {
+    /// The percent encoding scheme for keys and values.
+    pub encoding: Encoding,
+    /// The string written between pairs, `&` by default.
+    /// Its ASCII characters are always percent encoded within keys and values.
+    pub separator: &'static str,
+    /// The character written between a key and its value, `=` by default.
+    /// It is always percent encoded within keys and values.
+    pub kv_delimiter: char,
+    /// Whether the leading `?` is written before the first pair.
+    pub include_prefix: bool,
+    /// The representation of pairs with an empty value.
+    pub empty_policy: EmptyPolicy,
+    /// Whether pairs with an empty value are omitted, see
+    /// [`skip_empty_values`](crate::QueryString::skip_empty_values).
+    pub skip_empty_values: bool,
+    /// Whether spaces are rendered as `+`, or `None` to follow the encoding, see
+    /// [`space_as_plus`](crate::QueryString::space_as_plus).
+    pub space_as_plus: Option<bool>,
+    /// Whether percent escapes use lowercase hexadecimal digits, see
+    /// [`lowercase_encoding`](crate::QueryString::lowercase_encoding).
+    pub lowercase_encoding: bool,
+    /// Whether `/`, `?`, `:` and `@` are percent encoded, see
+    /// [`encode_path_chars`](crate::QueryString::encode_path_chars).
+    pub encode_path_chars: bool,
+    /// Whether pairs with the same key are rendered adjacently, see
+    /// [`grouped`](crate::QueryString::grouped).
+    pub grouped: bool,
+}
+
+impl Default for QueryStringOptions {
+    fn default() -> Self {
+        Self {
+            encoding: Encoding::default(),
+            separator: "&",
+            kv_delimiter: '=',
+            include_prefix: true,
+            empty_policy: EmptyPolicy::default(),
+            skip_empty_values: false,
+            space_as_plus: None,
+            lowercase_encoding: false,
+            encode_path_chars: false,
+            grouped: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QueryString;
+
+    #[test]
+    fn test_options() {
+        assert_eq!(
+            QueryString::dynamic().options(),
+            QueryStringOptions::default()
+        );
+
+        let qs = QueryString::rfc3986()
+            .with_kv_delimiter(':')
+            .empty_value_policy(EmptyPolicy::BareKey);
+        assert_eq!(
+            qs.options(),
+            QueryStringOptions {
+                encoding: Encoding::Rfc3986,
+                kv_delimiter: ':',
+                empty_policy: EmptyPolicy::BareKey,
+                ..QueryStringOptions::default()
+            }
+        );
+
+        let options = QueryString::cookie().options();
+        assert_eq!(options.separator, "; ");
+        assert!(!options.include_prefix);
+
+        let qs = QueryString::dynamic()
+            .skip_empty_values()
+            .space_as_plus(true)
+            .lowercase_encoding()
+            .encode_path_chars()
+            .grouped();
+        assert_eq!(
+            qs.options(),
+            QueryStringOptions {
+                skip_empty_values: true,
+                space_as_plus: Some(true),
+                lowercase_encoding: true,
+                encode_path_chars: true,
+                grouped: true,
+                ..QueryStringOptions::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_with_options() {
+        let options = QueryStringOptions {
+            encoding: Encoding::Form,
+            separator: ";",
+            include_prefix: false,
+            ..QueryStringOptions::default()
+        };
+
+        let mut a = QueryString::with_options(options).with_value("a b", "c;d");
+        let b = QueryString::with_options(options).with_value("e", 1);
+        assert_eq!(a.to_string(), "a+b=c%3Bd");
+        assert_eq!(b.to_string(), "e=1");
+        assert_eq!(a.split_off(0).options(), options);
+    }
+
+    #[test]
+    fn test_with_options_rendering_flags() {
+        let options = QueryStringOptions {
+            skip_empty_values: true,
+            space_as_plus: Some(true),
+            lowercase_encoding: true,
+            encode_path_chars: true,
+            grouped: true,
+            ..QueryStringOptions::default()
+        };
+
+        let qs = QueryString::with_options(options)
+            .with_value("a", "b c/ü")
+            .with_value("d", "")
+            .with_value("e", 1)
+            .with_value("a", 2);
+        assert_eq!(qs.to_string(), "?a=b+c%2f%c3%bc&a=2&e=1");
+    }
+}
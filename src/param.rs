@@ -0,0 +1,74 @@
+use crate::QueryString;
+
+/// A strongly typed query parameter name, e.g. an enum of the parameters an API accepts.
+///
+/// ## Example
+///
+/// ```
+/// use query_string_builder::{QueryParam, QueryString};
+///
+/// enum Search {
+///     Query,
+///     Page,
+/// }
+///
+/// impl QueryParam for Search {
+///     fn name(&self) -> &str {
+///         match self {
+///             Search::Query => "q",
+///             Search::Page => "page",
+///         }
+///     }
+/// }
+///
+/// let qs = QueryString::dynamic()
+///             .with_param(Search::Query, "apple")
+///             .with_param(Search::Page, 2);
+///
+/// assert_eq!(
+///     format!("https://example.com/{qs}"),
+///     "https://example.com/?q=apple&page=2"
+/// );
+/// ```
+pub trait QueryParam {
+    /// Gets the decoded key of the parameter.
+    fn name(&self) -> &str;
+}
+
+impl<P: QueryParam + ?Sized> QueryParam for &P {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+}
+
+impl QueryString {
+    /// Appends a key-value pair using the [name](QueryParam::name) of the parameter as the key.
+    ///
+    /// See [`QueryParam`] for an example.
+    pub fn with_param<P: QueryParam, V: ToString>(self, param: P, value: V) -> Self {
+        self.with_value(param.name(), value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{QueryParam, QueryString};
+
+    struct Param(&'static str);
+
+    impl QueryParam for Param {
+        fn name(&self) -> &str {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_with_param() {
+        let key = Param("a b");
+        let qs = QueryString::dynamic()
+            .with_param(&key, 1)
+            .with_param(Param("c"), "d&e");
+        assert_eq!(qs.get("a b"), Some("1"));
+        assert_eq!(qs.to_string(), "?a%20b=1&c=d%26e");
+    }
+}
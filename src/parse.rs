@@ -0,0 +1,403 @@
+use crate::encoding::Encoder;
+use crate::{Encoding, Kvp, ParseError, QueryString};
+use percent_encoding::percent_decode_str;
+use std::borrow::Cow;
+use std::io::BufRead;
+use std::str::FromStr;
+
+impl QueryString {
+    /// Parses a percent encoded query string, with or without the leading `?`.
+    ///
+    /// Keys without a `=` sign are added as bare flags, empty segments are skipped.
+    /// Plus signs are kept as-is rather than decoded to spaces, since this crate
+    /// always encodes them. Use [`parse_form`](Self::parse_form) for query strings rendered
+    /// using [`Encoding::Form`] or [`space_as_plus`](Self::space_as_plus).
+    ///
+    /// Values that do not decode to valid UTF-8, e.g. binary tokens, are kept percent
+    /// encoded and can be read using [`get_bytes`](Self::get_bytes). Keys must always
+    /// decode to valid UTF-8.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::parse("?q=%F0%9F%8D%8E%20apple&page=2&verbose").unwrap();
+    ///
+    /// assert_eq!(qs.get("q"), Some("🍎 apple"));
+    /// assert_eq!(qs.get("page"), Some("2"));
+    /// assert_eq!(qs.to_string(), "?q=%F0%9F%8D%8E%20apple&page=2&verbose");
+    /// ```
+    pub fn parse(s: &str) -> Result<QueryString, ParseError> {
+        parse_segments(s, QueryString::dynamic(), false)
+    }
+
+    /// Parses a query string encoded like HTML forms, with or without the leading `?`.
+    ///
+    /// Unlike [`parse`](Self::parse), plus signs are decoded to spaces, which makes this the
+    /// inverse of rendering with [`Encoding::Form`] or [`space_as_plus`](Self::space_as_plus).
+    /// The returned builder uses [`Encoding::Form`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::parse_form("?q=b+c%2Bd&page=2").unwrap();
+    ///
+    /// assert_eq!(qs.get("q"), Some("b c+d"));
+    /// assert_eq!(qs.to_string(), "?q=b+c%2Bd&page=2");
+    /// ```
+    pub fn parse_form(s: &str) -> Result<QueryString, ParseError> {
+        parse_segments(s, QueryString::form(), true)
+    }
+
+    /// Reads a query string from lines of `key=value` pairs.
+    ///
+    /// Each line is split at the first `=` sign and both sides are percent decoded.
+    /// Lines without a `=` sign are added as bare flags. Leading and trailing whitespace,
+    /// including the `\r` of CRLF line endings, is trimmed. Blank lines and lines
+    /// starting with `#` are skipped.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let config = "# search\nq=%F0%9F%8D%8E%20apple\n\npage=2\n";
+    /// let qs = QueryString::from_lines(config.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(qs.to_string(), "?q=%F0%9F%8D%8E%20apple&page=2");
+    /// ```
+    pub fn from_lines<R: BufRead>(reader: R) -> Result<QueryString, ParseError> {
+        let mut qs = QueryString::dynamic();
+        for line in reader.lines() {
+            let line = line?;
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            qs.pairs.push(parse_pair(trimmed, false)?);
+        }
+        Ok(qs)
+    }
+
+    /// Appends the pairs of an already percent encoded query string fragment, e.g. `a=1&b=2`.
+    ///
+    /// The pairs are decoded like in [`parse`](Self::parse), so that they are encoded
+    /// again using this builder's configuration when rendering.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_raw_fragment("tag=fruit%20salad&page=2")
+    ///             .unwrap();
+    ///
+    /// assert_eq!(qs.get("tag"), Some("fruit salad"));
+    /// assert_eq!(qs.to_string(), "?q=apple&tag=fruit%20salad&page=2");
+    /// ```
+    pub fn with_raw_fragment(mut self, fragment: &str) -> Result<Self, ParseError> {
+        let fragment = fragment.strip_prefix('?').unwrap_or(fragment);
+        for segment in fragment.split('&').filter(|segment| !segment.is_empty()) {
+            self.push_pair(parse_pair(segment, false)?);
+        }
+        Ok(self)
+    }
+
+    /// Appends the pairs of an already percent encoded query string fragment, e.g. `a=1&b=2`,
+    /// without decoding them. The keys and values are written verbatim when rendering,
+    /// like pairs added using [`with_value_raw`](Self::with_value_raw).
+    ///
+    /// Only use this for fragments from trusted sources, since they are not validated.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple")
+    ///             .with_raw_fragment_verbatim("sig=a%2Fb+c&debug");
+    ///
+    /// assert_eq!(qs.get("sig"), Some("a%2Fb+c"));
+    /// assert_eq!(qs.to_string(), "?q=apple&sig=a%2Fb+c&debug");
+    /// ```
+    pub fn with_raw_fragment_verbatim(mut self, fragment: &str) -> Self {
+        let fragment = fragment.strip_prefix('?').unwrap_or(fragment);
+        for segment in fragment.split('&').filter(|segment| !segment.is_empty()) {
+            let pair = match segment.split_once('=') {
+                Some((key, value)) => Kvp::raw(key.to_string(), value.to_string()),
+                None => Kvp {
+                    raw: true,
+                    ..Kvp::flag(segment.to_string())
+                },
+            };
+            self.push_pair(pair);
+        }
+        self
+    }
+}
+
+impl FromStr for QueryString {
+    type Err = ParseError;
+
+    /// Parses a percent encoded query string, see [`QueryString::parse`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs: QueryString = "?a=1&b=2".parse().unwrap();
+    ///
+    /// assert_eq!(qs.get("b"), Some("2"));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        QueryString::parse(s)
+    }
+}
+
+impl TryFrom<&str> for QueryString {
+    type Error = ParseError;
+
+    /// Parses a percent encoded query string, see [`QueryString::parse`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::try_from("a=1&b=2").unwrap();
+    ///
+    /// assert_eq!(qs.to_string(), "?a=1&b=2");
+    /// ```
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        QueryString::parse(s)
+    }
+}
+
+/// Parses the `&` separated pairs of a query string into the builder.
+fn parse_segments(
+    s: &str,
+    mut qs: QueryString,
+    plus_as_space: bool,
+) -> Result<QueryString, ParseError> {
+    let s = s.strip_prefix('?').unwrap_or(s);
+    for segment in s.split('&').filter(|segment| !segment.is_empty()) {
+        qs.pairs.push(parse_pair(segment, plus_as_space)?);
+    }
+    Ok(qs)
+}
+
+/// Parses a single `key=value` pair, or a bare key, optionally decoding `+` to a space.
+///
+/// Values that do not decode to valid UTF-8 are kept percent encoded, normalized
+/// using [`Encoding::Query`] so that they are always rendered safely.
+fn parse_pair(segment: &str, plus_as_space: bool) -> Result<Kvp, ParseError> {
+    let segment: Cow<str> = if plus_as_space && segment.contains('+') {
+        Cow::Owned(segment.replace('+', " "))
+    } else {
+        Cow::Borrowed(segment)
+    };
+
+    match segment.split_once('=') {
+        Some((key, value)) => match decode(value) {
+            Ok(decoded) => Ok(Kvp::new(decode(key)?, decoded)),
+            Err(_) => {
+                let bytes: Vec<u8> = percent_decode_str(value).collect();
+                let mut encoded = String::new();
+                Encoder::new(Encoding::Query)
+                    .write_bytes(&mut encoded, &bytes)
+                    .expect("writing to a String never fails");
+                Ok(Kvp::value_encoded(decode(key)?, encoded))
+            }
+        },
+        None => Ok(Kvp::flag(decode(&segment)?)),
+    }
+}
+
+fn decode(s: &str) -> Result<String, ParseError> {
+    percent_decode_str(s)
+        .decode_utf8()
+        .map(|decoded| decoded.into_owned())
+        .map_err(|_| ParseError::InvalidUtf8(s.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use std::io::{self, Read};
+
+    #[test]
+    fn test_parse() {
+        let qs = QueryString::parse("q=Gr%C3%BCnkohl&&a=b=c&1+1&empty=&").unwrap();
+        assert_eq!(
+            qs.as_pairs(),
+            [("q", "Grünkohl"), ("a", "b=c"), ("1+1", ""), ("empty", "")]
+        );
+        assert_eq!(qs.to_string(), "?q=Gr%C3%BCnkohl&a=b%3Dc&1%2B1&empty=");
+
+        assert!(QueryString::parse("").unwrap().is_empty());
+        assert!(QueryString::parse("?").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_form() {
+        let rendered = QueryString::form().with_value("a", "b c+d").to_string();
+        assert_eq!(rendered, "?a=b+c%2Bd");
+
+        // `parse` keeps plus signs, so form encoded spaces do not round-trip.
+        assert_eq!(
+            QueryString::parse(&rendered).unwrap().get("a"),
+            Some("b+c+d")
+        );
+
+        let qs = QueryString::parse_form(&rendered).unwrap();
+        assert_eq!(qs.as_pairs(), [("a", "b c+d")]);
+        assert_eq!(qs.encoding(), Encoding::Form);
+        assert_eq!(qs.to_string(), rendered);
+
+        let qs = QueryString::parse_form("x+y&k=%FF+").unwrap();
+        assert_eq!(qs.as_pairs()[0], ("x y", ""));
+        assert_eq!(qs.get_bytes("k"), Some(b"\xFF ".to_vec()));
+    }
+
+    #[test]
+    fn test_parse_invalid_utf8() {
+        let err = QueryString::parse("?%FF=a").unwrap_err();
+        assert!(matches!(&err, ParseError::InvalidUtf8(input) if input == "%FF"));
+        assert_eq!(err.to_string(), "\"%FF\" does not decode to valid UTF-8");
+    }
+
+    #[test]
+    fn test_parse_binary_value() {
+        let qs = QueryString::parse("?sig=%FF%00&q=a%20b").unwrap();
+        assert_eq!(qs.get_bytes("sig"), Some(vec![0xFF, 0x00]));
+        assert_eq!(qs.get_bytes("q"), Some(b"a b".to_vec()));
+        assert_eq!(qs.get("sig"), Some("%FF%00"));
+        assert_eq!(qs.to_string(), "?sig=%FF%00&q=a%20b");
+
+        let qs = QueryString::parse("?sig=%ff a+#").unwrap();
+        assert_eq!(qs.get_bytes("sig"), Some(b"\xFF a+#".to_vec()));
+        assert_eq!(qs.to_string(), "?sig=%FF%20a%2B%23");
+    }
+
+    #[test]
+    fn test_from_str_and_try_from() {
+        fn parse_both(s: &str) -> Result<(QueryString, QueryString), ParseError> {
+            Ok((s.parse()?, QueryString::try_from(s)?))
+        }
+
+        let (a, b) = parse_both("?q=a%20b&flag").unwrap();
+        assert_eq!(a, QueryString::parse("?q=a%20b&flag").unwrap());
+        assert_eq!(a, b);
+
+        assert!(matches!(
+            parse_both("%FF=a"),
+            Err(ParseError::InvalidUtf8(_))
+        ));
+    }
+
+    #[test]
+    fn test_raw_fragment() {
+        let qs = QueryString::form()
+            .with_raw_fragment("?a=1%2B1&&b")
+            .unwrap()
+            .with_raw_fragment("")
+            .unwrap();
+        assert_eq!(qs.as_pairs(), [("a", "1+1"), ("b", "")]);
+        assert_eq!(qs.to_string(), "?a=1%2B1&b");
+
+        let err = QueryString::dynamic().with_raw_fragment("%FF").unwrap_err();
+        assert!(matches!(err, ParseError::InvalidUtf8(_)));
+    }
+
+    #[test]
+    fn test_raw_fragment_verbatim() {
+        let qs = QueryString::form()
+            .with_value("x", "a b")
+            .with_raw_fragment_verbatim("?a=1+1&&b%20c&d=e=f");
+        assert_eq!(qs.len(), 4);
+        assert_eq!(qs.to_string(), "?x=a+b&a=1+1&b%20c&d=e=f");
+    }
+
+    #[test]
+    fn test_from_lines() {
+        let input =
+            "# comment\n  # indented comment\nq=🍎 apple\r\n\n   \n  url=a=b\r\n\tverbose \n";
+        let qs = QueryString::from_lines(input.as_bytes()).unwrap();
+        assert_eq!(
+            qs.as_pairs(),
+            [("q", "🍎 apple"), ("url", "a=b"), ("verbose", "")]
+        );
+        assert_eq!(qs.to_string(), "?q=%F0%9F%8D%8E%20apple&url=a%3Db&verbose");
+    }
+
+    proptest::proptest! {
+        /// Rendering and parsing a query string yields the original pairs.
+        #[test]
+        fn test_round_trip(pairs in proptest::collection::vec(("(?s).*", "(?s).*"), 0..8)) {
+            let expected: Vec<_> = pairs.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+
+            for encoding in [Encoding::Query, Encoding::Rfc3986] {
+                let mut qs = QueryString::with_encoding(encoding);
+                for (key, value) in &pairs {
+                    qs.push(key, value);
+                }
+
+                let parsed = QueryString::parse(&qs.to_string()).unwrap();
+                proptest::prop_assert_eq!(parsed.as_pairs(), expected.clone());
+            }
+
+            // Spaces rendered as `+` only round-trip when parsing them as forms.
+            let builders = [
+                QueryString::form(),
+                QueryString::dynamic().space_as_plus(true),
+                QueryString::rfc3986().space_as_plus(true),
+            ];
+            for mut qs in builders {
+                for (key, value) in &pairs {
+                    qs.push(key, value);
+                }
+
+                let parsed = QueryString::parse_form(&qs.to_string()).unwrap();
+                proptest::prop_assert_eq!(parsed.as_pairs(), expected.clone());
+            }
+        }
+
+        /// Parsing bare keys yields flags that render the same way.
+        #[test]
+        fn test_round_trip_flags(keys in proptest::collection::vec("(?s).+", 0..8)) {
+            let mut qs = QueryString::dynamic();
+            for key in &keys {
+                qs = qs.with_flag(key);
+            }
+
+            let rendered = qs.to_string();
+            let parsed = QueryString::parse(&rendered).unwrap();
+            proptest::prop_assert_eq!(parsed.to_string(), rendered);
+        }
+    }
+
+    #[test]
+    fn test_from_lines_io_error() {
+        struct Failing;
+
+        impl Read for Failing {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("broken"))
+            }
+        }
+
+        let err = QueryString::from_lines(io::BufReader::new(Failing)).unwrap_err();
+        assert!(matches!(err, ParseError::Io(_)));
+        assert!(err.source().is_some());
+        assert_eq!(err.to_string(), "failed to read the query string: broken");
+    }
+}
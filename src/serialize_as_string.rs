@@ -0,0 +1,85 @@
+//! Serializes a [`QueryString`] as its rendered string, e.g. `"?a=1&b=2"`, for use with
+//! `#[serde(with = "query_string_builder::serialize_as_string")]`.
+//!
+//! Deserializing parses the string using [`QueryString::parse`], so the pairs are restored
+//! while the builder's configuration is reset to the defaults.
+//!
+//! ## Example
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use query_string_builder::QueryString;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(with = "query_string_builder::serialize_as_string")]
+//!     query: QueryString,
+//! }
+//!
+//! let record = Record {
+//!     query: QueryString::dynamic()
+//!         .with_value("q", "🍎 apple")
+//!         .with_value("page", 2),
+//! };
+//!
+//! let json = serde_json::to_string(&record).unwrap();
+//! assert_eq!(json, r#"{"query":"?q=%F0%9F%8D%8E%20apple&page=2"}"#);
+//!
+//! let record: Record = serde_json::from_str(&json).unwrap();
+//! assert_eq!(record.query.get("q"), Some("🍎 apple"));
+//! ```
+
+use crate::QueryString;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Serializes the builder as its rendered query string.
+pub fn serialize<S: Serializer>(qs: &QueryString, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(qs)
+}
+
+/// Deserializes a builder by parsing a percent encoded query string.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<QueryString, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    QueryString::parse(&s).map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::QueryString;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Record {
+        id: u32,
+        #[serde(with = "super")]
+        query: QueryString,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let record = Record {
+            id: 1,
+            query: QueryString::dynamic().with_value("a", "1&2").with_flag("b"),
+        };
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(json, r#"{"id":1,"query":"?a=1%262&b"}"#);
+
+        let parsed: Record = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.query, record.query);
+
+        let empty: Record = serde_json::from_str(r#"{"id":2,"query":""}"#).unwrap();
+        assert!(empty.query.is_empty());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let err = serde_json::from_str::<Record>(r#"{"id":1,"query":"?%FF=a"}"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(r#""%FF" does not decode to valid UTF-8"#));
+
+        assert!(serde_json::from_str::<Record>(r#"{"id":1,"query":42}"#).is_err());
+    }
+}
@@ -1,7 +1,8 @@
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter, Write};
 
-use crate::QUERY;
+use crate::encoding::is_never_encoded;
+use crate::{QueryString, QUERY};
 use percent_encoding::utf8_percent_encode;
 
 /// A type alias for the [`WrappedQueryString`] root.
@@ -31,7 +32,7 @@ pub type QueryStringSimple = WrappedQueryString<RootMarker, EmptyValue>;
 pub struct WrappedQueryString<B, T>
 where
     B: ConditionalDisplay + Identifiable,
-    T: Display,
+    T: PairDisplay,
 {
     base: BaseOption<B>,
     value: KvpOption<T>,
@@ -53,6 +54,47 @@ where
     value: V,
 }
 
+/// A helper type to track a borrowed slice of key-value pairs in [`WrappedQueryString`].
+///
+/// ## Example
+///
+/// ```
+/// use query_string_builder::{KvpSlice, QueryString, QueryStringSimple, WrappedQueryString};
+///
+/// fn search<'a>(
+///     pairs: &'a [(&'a str, u32)],
+/// ) -> WrappedQueryString<QueryStringSimple, KvpSlice<'a, &'a str, u32>> {
+///     QueryString::simple_from_slice(pairs)
+/// }
+///
+/// assert_eq!(search(&[("page", 2)]).to_string(), "?page=2");
+/// ```
+pub struct KvpSlice<'a, K, V>
+where
+    K: Display,
+    V: Display,
+{
+    pairs: &'a [(K, V)],
+}
+
+/// The representation of a boolean value used by [`WrappedQueryString::with_bool`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoolStyle {
+    /// Renders `true` or `false`.
+    #[default]
+    TrueFalse,
+    /// Renders `1` or `0`.
+    OneZero,
+    /// Renders `yes` or `no`.
+    YesNo,
+}
+
+/// A helper type to render a boolean value in a [`BoolStyle`].
+pub struct StyledBool {
+    value: bool,
+    style: BoolStyle,
+}
+
 enum BaseOption<B> {
     Some(B),
     None,
@@ -74,7 +116,7 @@ pub struct EmptyValue(());
 impl<B, T> WrappedQueryString<B, T>
 where
     B: ConditionalDisplay + Identifiable,
-    T: Display,
+    T: PairDisplay,
 {
     /// Creates a new, empty query string builder.
     pub(crate) fn new() -> WrappedQueryString<RootMarker, EmptyValue> {
@@ -148,13 +190,108 @@ where
         }
     }
 
-    /// Determines the number of key-value pairs currently in the builder.
-    pub fn len(&self) -> usize {
-        if self.is_empty() {
-            return 0;
+    /// Appends all key-value pairs of the slice to the query string, borrowing the slice
+    /// rather than copying its entries.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let pairs = [("q", "apple"), ("category", "fruits and vegetables")];
+    ///
+    /// let qs = QueryString::simple()
+    ///             .with_slice(&pairs)
+    ///             .with_value("page", 2);
+    ///
+    /// assert_eq!(qs.len(), 3);
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple&category=fruits%20and%20vegetables&page=2"
+    /// );
+    /// ```
+    pub fn with_slice<K: Display, V: Display>(
+        self,
+        pairs: &[(K, V)],
+    ) -> WrappedQueryString<Self, KvpSlice<'_, K, V>> {
+        let value = if pairs.is_empty() {
+            KvpOption::None
+        } else {
+            KvpOption::Some(KvpSlice { pairs })
+        };
+
+        WrappedQueryString {
+            base: BaseOption::Some(self),
+            value,
+        }
+    }
+
+    /// Appends a key-value pair with a boolean value rendered in the specified style.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::{BoolStyle, QueryString};
+    ///
+    /// let qs = QueryString::simple()
+    ///             .with_bool("tasty", true, BoolStyle::TrueFalse)
+    ///             .with_bool("ripe", false, BoolStyle::OneZero)
+    ///             .with_bool("organic", true, BoolStyle::YesNo);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?tasty=true&ripe=0&organic=yes"
+    /// );
+    /// ```
+    pub fn with_bool<K: Display>(
+        self,
+        key: K,
+        value: bool,
+        style: BoolStyle,
+    ) -> WrappedQueryString<Self, Kvp<K, StyledBool>> {
+        self.with_value(key, StyledBool { value, style })
+    }
+
+    /// Appends a key-value pair to the query string if the condition is met.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let debug = false;
+    ///
+    /// let qs = QueryString::simple()
+    ///             .with_value("q", "apple")
+    ///             .with_value_if(debug, "verbose", true)
+    ///             .with_value_if(!debug, "page", 2);
+    ///
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?q=apple&page=2"
+    /// );
+    /// ```
+    pub fn with_value_if<K: Display, V: Display>(
+        self,
+        cond: bool,
+        key: K,
+        value: V,
+    ) -> WrappedQueryString<Self, Kvp<K, V>> {
+        let value = if cond {
+            KvpOption::Some(Kvp { key, value })
+        } else {
+            KvpOption::None
+        };
+
+        WrappedQueryString {
+            base: BaseOption::Some(self),
+            value,
         }
+    }
 
-        1 + self.base.len()
+    /// Determines the number of key-value pairs currently in the builder.
+    pub fn len(&self) -> usize {
+        Identifiable::len(self)
     }
 
     /// Determines if the builder is currently empty.
@@ -171,12 +308,116 @@ where
 
         self.base.is_empty()
     }
+
+    /// Renders the query string into the specified writer.
+    ///
+    /// This produces the same output as the [`Display`] implementation, but allows
+    /// streaming the query string into an existing buffer without an intermediate `String`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::simple()
+    ///             .with_value("q", "apple")
+    ///             .with_value("category", "fruits and vegetables");
+    ///
+    /// let mut url = String::from("https://example.com/");
+    /// qs.write_into(&mut url).unwrap();
+    ///
+    /// assert_eq!(
+    ///     url,
+    ///     "https://example.com/?q=apple&category=fruits%20and%20vegetables"
+    /// );
+    /// ```
+    pub fn write_into<W: Write + ?Sized>(&self, w: &mut W) -> fmt::Result {
+        write!(w, "{self}")
+    }
+
+    /// Collects the key-value pairs into a dynamic [`QueryString`], e.g. to inspect
+    /// or modify them further.
+    ///
+    /// For the reverse direction, borrow the pairs of the dynamic builder using
+    /// [`QueryString::as_pairs`] and append them using [`with_slice`](Self::with_slice).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    ///
+    /// let slim = QueryString::simple()
+    ///             .with_value("q", "apple")
+    ///             .with_opt_value("page", None::<u32>)
+    ///             .with_value("size", 20);
+    ///
+    /// let mut qs = slim.to_dynamic();
+    /// qs.push("page", 2);
+    /// assert_eq!(qs.to_string(), "?q=apple&size=20&page=2");
+    ///
+    /// let pairs = qs.as_pairs();
+    /// let slim = QueryString::simple().with_slice(&pairs);
+    /// assert_eq!(slim.to_string(), "?q=apple&size=20&page=2");
+    /// ```
+    pub fn to_dynamic(&self) -> QueryString {
+        let mut qs = QueryString::dynamic();
+        Identifiable::collect_pairs(self, &mut qs);
+        qs
+    }
+}
+
+/// Implemented by the values stored in a [`WrappedQueryString`] node.
+pub trait PairDisplay: Display {
+    /// Gets the number of key-value pairs rendered by this value.
+    fn pair_count(&self) -> usize;
+
+    /// Appends the key-value pairs of this value to the dynamic builder.
+    fn collect_pairs(&self, qs: &mut QueryString);
+}
+
+impl PairDisplay for EmptyValue {
+    fn pair_count(&self) -> usize {
+        0
+    }
+
+    fn collect_pairs(&self, _qs: &mut QueryString) {}
+}
+
+impl<K, V> PairDisplay for Kvp<K, V>
+where
+    K: Display,
+    V: Display,
+{
+    fn pair_count(&self) -> usize {
+        1
+    }
+
+    fn collect_pairs(&self, qs: &mut QueryString) {
+        qs.push(&self.key, &self.value);
+    }
+}
+
+impl<K, V> PairDisplay for KvpSlice<'_, K, V>
+where
+    K: Display,
+    V: Display,
+{
+    fn pair_count(&self) -> usize {
+        self.pairs.len()
+    }
+
+    fn collect_pairs(&self, qs: &mut QueryString) {
+        for (key, value) in self.pairs {
+            qs.push(key, value);
+        }
+    }
 }
 
 pub trait Identifiable {
     fn is_root(&self) -> bool;
     fn is_empty(&self) -> bool;
     fn len(&self) -> usize;
+    fn collect_pairs(&self, qs: &mut QueryString);
 }
 
 pub trait ConditionalDisplay {
@@ -195,6 +436,10 @@ impl Identifiable for RootMarker {
     fn len(&self) -> usize {
         unreachable!()
     }
+
+    fn collect_pairs(&self, _qs: &mut QueryString) {
+        unreachable!()
+    }
 }
 
 impl ConditionalDisplay for RootMarker {
@@ -230,7 +475,7 @@ where
 impl<B, T> ConditionalDisplay for WrappedQueryString<B, T>
 where
     B: ConditionalDisplay + Identifiable,
-    T: Display,
+    T: PairDisplay,
 {
     fn cond_fmt(&self, should_display: bool, f: &mut Formatter<'_>) -> Result<usize, fmt::Error> {
         let depth = if !should_display {
@@ -286,7 +531,7 @@ where
 impl<B, T> Identifiable for WrappedQueryString<B, T>
 where
     B: ConditionalDisplay + Identifiable,
-    T: Display,
+    T: PairDisplay,
 {
     fn is_root(&self) -> bool {
         match self.base {
@@ -303,11 +548,20 @@ where
     }
 
     fn len(&self) -> usize {
-        match self.value {
-            KvpOption::Some(_) => 1 + self.base.len(),
+        match &self.value {
+            KvpOption::Some(value) => value.pair_count() + self.base.len(),
             KvpOption::None => self.base.len(),
         }
     }
+
+    fn collect_pairs(&self, qs: &mut QueryString) {
+        if let BaseOption::Some(base) = &self.base {
+            base.collect_pairs(qs);
+        }
+        if let KvpOption::Some(value) = &self.value {
+            value.collect_pairs(qs);
+        }
+    }
 }
 
 impl<T> KvpOption<T> {
@@ -325,6 +579,20 @@ impl Display for EmptyValue {
     }
 }
 
+impl Display for StyledBool {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let value = match (self.style, self.value) {
+            (BoolStyle::TrueFalse, true) => "true",
+            (BoolStyle::TrueFalse, false) => "false",
+            (BoolStyle::OneZero, true) => "1",
+            (BoolStyle::OneZero, false) => "0",
+            (BoolStyle::YesNo, true) => "yes",
+            (BoolStyle::YesNo, false) => "no",
+        };
+        f.write_str(value)
+    }
+}
+
 impl<T> Display for BaseOption<T>
 where
     T: Display,
@@ -355,16 +623,62 @@ where
     V: Display,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        Display::fmt(&utf8_percent_encode(&self.key.to_string(), QUERY), f)?;
-        f.write_char('=')?;
-        Display::fmt(&utf8_percent_encode(&self.value.to_string(), QUERY), f)
+        fmt_pair(&self.key, &self.value, f)
+    }
+}
+
+impl<K, V> Display for KvpSlice<'_, K, V>
+where
+    K: Display,
+    V: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, (key, value)) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                f.write_char('&')?;
+            }
+            fmt_pair(key, value, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders a percent encoded key-value pair.
+pub(crate) fn fmt_pair<K: Display, V: Display>(
+    key: &K,
+    value: &V,
+    f: &mut Formatter<'_>,
+) -> fmt::Result {
+    write!(PercentEncoder(f), "{key}")?;
+    f.write_char('=')?;
+    write!(PercentEncoder(f), "{value}")
+}
+
+/// A writer that percent encodes everything written to it before passing it on,
+/// which avoids rendering keys and values into intermediate strings.
+///
+/// Since percent encoding operates on individual bytes, it does not matter how
+/// the [`Display`] implementation splits its output across calls.
+struct PercentEncoder<'a, W: Write + ?Sized>(&'a mut W);
+
+impl<W: Write + ?Sized> Write for PercentEncoder<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // Fast path for values such as integers that do not require any encoding.
+        if s.bytes().all(is_never_encoded) {
+            return self.0.write_str(s);
+        }
+
+        for chunk in utf8_percent_encode(s, QUERY) {
+            self.0.write_str(chunk)?;
+        }
+        Ok(())
     }
 }
 
 impl<B, T> Display for WrappedQueryString<B, T>
 where
     B: ConditionalDisplay + Identifiable,
-    T: Display,
+    T: PairDisplay,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let should_display = !self.value.is_empty();
@@ -381,7 +695,7 @@ where
 impl<B, T> Debug for WrappedQueryString<B, T>
 where
     B: ConditionalDisplay + Identifiable,
-    T: Display,
+    T: PairDisplay,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         Display::fmt(self, f)
@@ -390,7 +704,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::slim::{BaseOption, EmptyValue, KvpOption};
+    use crate::slim::{BaseOption, BoolStyle, EmptyValue, KvpOption};
     use crate::QueryString;
 
     #[test]
@@ -444,6 +758,47 @@ mod tests {
         assert_eq!(qs.to_string(), "?q=Gr%C3%BCnkohl&category=Gem%C3%BCse");
     }
 
+    #[test]
+    fn test_to_dynamic() {
+        assert!(QueryString::simple().to_dynamic().is_empty());
+
+        let pairs = [("c", 3), ("d", 4)];
+        let slim = QueryString::simple()
+            .with_value("a b", 1)
+            .with_opt_value("x", None::<u8>)
+            .with_slice(&pairs)
+            .with_slice::<&str, u8>(&[])
+            .with_value("e", "&");
+        let qs = slim.to_dynamic();
+        assert_eq!(qs.len(), slim.len());
+        assert_eq!(qs.to_string(), slim.to_string());
+        assert_eq!(
+            qs.as_pairs(),
+            [("a b", "1"), ("c", "3"), ("d", "4"), ("e", "&")]
+        );
+    }
+
+    #[test]
+    fn test_fragmented_display() {
+        use std::fmt::{self, Display, Formatter, Write};
+
+        struct Fragmented;
+
+        impl Display for Fragmented {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("a&")?;
+                f.write_char('🍎')?;
+                write!(f, " {}={:>3}", 1, 2)
+            }
+        }
+
+        let qs = QueryString::simple().with_value(Fragmented, Fragmented);
+        assert_eq!(
+            qs.to_string(),
+            "?a%26%F0%9F%8D%8E%201%3D%20%202=a%26%F0%9F%8D%8E%201%3D%20%202"
+        );
+    }
+
     #[test]
     fn test_emoji() {
         let qs = QueryString::simple()
@@ -478,6 +833,78 @@ mod tests {
         assert_eq!(qs.len(), 4); // not five!
     }
 
+    #[test]
+    fn test_value_if() {
+        let qs = QueryString::simple()
+            .with_value_if(false, "a", 1)
+            .with_value_if(true, "b", 2)
+            .with_value_if(false, "c", 3);
+
+        assert!(!qs.is_empty());
+        assert_eq!(qs.len(), 1);
+        assert_eq!(qs.to_string(), "?b=2");
+
+        let qs = QueryString::simple().with_value_if(false, "a", 1);
+        assert!(qs.is_empty());
+        assert_eq!(qs.len(), 0);
+        assert_eq!(qs.to_string(), "");
+    }
+
+    #[test]
+    fn test_slice() {
+        let pairs = [("q", "🍎 apple"), ("tasty", "true")];
+        let empty: [(&str, &str); 0] = [];
+
+        let qs = QueryString::simple()
+            .with_slice(&empty)
+            .with_value("a", 1)
+            .with_slice(&pairs)
+            .with_slice(&empty);
+
+        assert!(!qs.is_empty());
+        assert_eq!(qs.len(), 3);
+        assert_eq!(qs.to_string(), "?a=1&q=%F0%9F%8D%8E%20apple&tasty=true");
+
+        let qs = QueryString::simple_from_slice(&pairs);
+        assert_eq!(qs.len(), 2);
+        assert_eq!(qs.to_string(), "?q=%F0%9F%8D%8E%20apple&tasty=true");
+
+        let qs = QueryString::simple_from_slice(&empty);
+        assert!(qs.is_empty());
+        assert_eq!(qs.len(), 0);
+        assert_eq!(qs.to_string(), "");
+    }
+
+    #[test]
+    fn test_bool() {
+        let qs = QueryString::simple()
+            .with_bool("a", true, BoolStyle::TrueFalse)
+            .with_bool("b", false, BoolStyle::TrueFalse)
+            .with_bool("c", true, BoolStyle::OneZero)
+            .with_bool("d", false, BoolStyle::OneZero)
+            .with_bool("e", true, BoolStyle::YesNo)
+            .with_bool("f", false, BoolStyle::default());
+
+        assert_eq!(qs.len(), 6);
+        assert_eq!(qs.to_string(), "?a=true&b=false&c=1&d=0&e=yes&f=false");
+    }
+
+    #[test]
+    fn test_write_into() {
+        let qs = QueryString::simple()
+            .with_value("q", "🍎 apple")
+            .with_opt_value("color", None::<&str>)
+            .with_value("tasty", true);
+
+        let mut buffer = String::from("https://example.com/");
+        qs.write_into(&mut buffer).unwrap();
+        assert_eq!(buffer, format!("https://example.com/{qs}"));
+
+        let mut buffer = String::new();
+        QueryString::simple().write_into(&mut buffer).unwrap();
+        assert_eq!(buffer, "");
+    }
+
     #[test]
     fn test_display() {
         assert_eq!(format!("{}", KvpOption::<i32>::None), "");
@@ -0,0 +1,145 @@
+use crate::QueryString;
+use std::fmt::{Display, Formatter};
+use time::OffsetDateTime;
+
+/// The representation of a timestamp used by [`QueryString::with_timestamp`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeFormat {
+    /// An RFC 3339 timestamp, e.g. `2024-05-01T12:30:00Z` or `2024-05-01T14:30:00.5+02:00`.
+    /// Fractional seconds are rendered only if present, and seconds of the UTC offset are truncated.
+    #[default]
+    Rfc3339,
+    /// Whole seconds since the Unix epoch, e.g. `1714566600`.
+    UnixSeconds,
+    /// Whole milliseconds since the Unix epoch, e.g. `1714566600000`.
+    UnixMillis,
+}
+
+/// A helper to render an [`OffsetDateTime`] in the specified [`TimeFormat`].
+struct DisplayTimestamp(OffsetDateTime, TimeFormat);
+
+impl Display for DisplayTimestamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let DisplayTimestamp(ts, format) = self;
+        match format {
+            TimeFormat::UnixSeconds => write!(f, "{}", ts.unix_timestamp()),
+            TimeFormat::UnixMillis => {
+                write!(f, "{}", ts.unix_timestamp_nanos().div_euclid(1_000_000))
+            }
+            TimeFormat::Rfc3339 => {
+                let year = ts.year();
+                if (0..=9999).contains(&year) {
+                    write!(f, "{year:04}")?;
+                } else {
+                    write!(f, "{year:+05}")?;
+                }
+
+                write!(
+                    f,
+                    "-{:02}-{:02}T{:02}:{:02}:{:02}",
+                    u8::from(ts.month()),
+                    ts.day(),
+                    ts.hour(),
+                    ts.minute(),
+                    ts.second()
+                )?;
+
+                let nanos = ts.nanosecond();
+                if nanos > 0 {
+                    let fraction = format!("{nanos:09}");
+                    write!(f, ".{}", fraction.trim_end_matches('0'))?;
+                }
+
+                let offset = ts.offset();
+                if offset.is_utc() {
+                    return f.write_str("Z");
+                }
+
+                let sign = if offset.is_negative() { '-' } else { '+' };
+                write!(
+                    f,
+                    "{sign}{:02}:{:02}",
+                    offset.whole_hours().unsigned_abs(),
+                    offset.minutes_past_hour().unsigned_abs()
+                )
+            }
+        }
+    }
+}
+
+impl QueryString {
+    /// Appends a key-value pair with the timestamp rendered in the specified format.
+    ///
+    /// The `:` and `+` of RFC 3339 timestamps are percent encoded like any other value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::{QueryString, TimeFormat};
+    /// use time::{OffsetDateTime, UtcOffset};
+    ///
+    /// let ts = OffsetDateTime::from_unix_timestamp(1_714_566_600).unwrap();
+    /// let local = ts.to_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_timestamp("since", ts, TimeFormat::Rfc3339)
+    ///             .with_timestamp("until", local, TimeFormat::Rfc3339)
+    ///             .with_timestamp("at", ts, TimeFormat::UnixSeconds);
+    ///
+    /// assert_eq!(qs.get("until"), Some("2024-05-01T14:30:00+02:00"));
+    /// assert_eq!(
+    ///     format!("https://example.com/{qs}"),
+    ///     "https://example.com/?since=2024-05-01T12:30:00Z&until=2024-05-01T14:30:00%2B02:00&at=1714566600"
+    /// );
+    /// ```
+    pub fn with_timestamp<K: ToString>(self, key: K, ts: OffsetDateTime, fmt: TimeFormat) -> Self {
+        self.with_value(key, DisplayTimestamp(ts, fmt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use time::format_description::well_known::Rfc3339;
+    use time::{Duration, UtcOffset};
+
+    fn format(ts: OffsetDateTime, format: TimeFormat) -> String {
+        DisplayTimestamp(ts, format).to_string()
+    }
+
+    #[test]
+    fn test_unix() {
+        let ts = OffsetDateTime::from_unix_timestamp_nanos(1_500_999_999).unwrap();
+        assert_eq!(format(ts, TimeFormat::UnixSeconds), "1");
+        assert_eq!(format(ts, TimeFormat::UnixMillis), "1500");
+
+        let ts = OffsetDateTime::UNIX_EPOCH - Duration::milliseconds(1500);
+        assert_eq!(format(ts, TimeFormat::UnixSeconds), "-2");
+        assert_eq!(format(ts, TimeFormat::UnixMillis), "-1500");
+    }
+
+    #[test]
+    fn test_rfc3339() {
+        let ts = OffsetDateTime::UNIX_EPOCH + Duration::nanoseconds(250_000_000);
+        assert_eq!(format(ts, TimeFormat::Rfc3339), "1970-01-01T00:00:00.25Z");
+
+        let offset = UtcOffset::from_hms(-5, -30, -15).unwrap();
+        let ts = OffsetDateTime::UNIX_EPOCH.to_offset(offset);
+        assert_eq!(format(ts, TimeFormat::Rfc3339), "1969-12-31T18:29:45-05:30");
+    }
+
+    proptest! {
+        /// Cross-checks the RFC 3339 output against the `time` crate's own formatting.
+        #[test]
+        fn test_rfc3339_matches_time(
+            nanos in 0i128..253_402_300_799_999_999_999,
+            offset_minutes in -1439i32..=1439,
+        ) {
+            let offset = UtcOffset::from_whole_seconds(offset_minutes * 60).unwrap();
+            let ts = OffsetDateTime::from_unix_timestamp_nanos(nanos).unwrap().to_offset(offset);
+            prop_assume!((0..=9999).contains(&ts.year()));
+            prop_assert_eq!(format(ts, TimeFormat::Rfc3339), ts.format(&Rfc3339).unwrap());
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::QueryString;
+use http::uri::{InvalidUri, Parts, PathAndQuery};
+
+impl QueryString {
+    /// Sets the query component of the specified [`http::uri::Parts`] to this query string,
+    /// replacing any existing query while keeping the path. If the parts do not have a path yet,
+    /// `/` is used. An empty query string removes the query component.
+    ///
+    /// Since the rendered query string is already percent encoded, this only fails if
+    /// characters were explicitly exempted from encoding, e.g. via
+    /// [`allow_literal`](QueryString::allow_literal) or [`with_value_raw`](QueryString::with_value_raw).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use http::Uri;
+    /// use query_string_builder::QueryString;
+    ///
+    /// let qs = QueryString::dynamic()
+    ///             .with_value("q", "apple pie")
+    ///             .with_value("page", 2);
+    ///
+    /// let mut parts = Uri::from_static("https://example.com/search?old=1").into_parts();
+    /// qs.apply_to_uri_parts(&mut parts).unwrap();
+    ///
+    /// let uri = Uri::from_parts(parts).unwrap();
+    /// assert_eq!(uri, "https://example.com/search?q=apple%20pie&page=2");
+    /// ```
+    pub fn apply_to_uri_parts(&self, parts: &mut Parts) -> Result<(), InvalidUri> {
+        let mut path_and_query = parts
+            .path_and_query
+            .as_ref()
+            .map_or("/", |pq| pq.path())
+            .to_string();
+        self.write_encoded(&mut path_and_query, "?")
+            .expect("writing to a String never fails");
+        let path_and_query = PathAndQuery::try_from(path_and_query)?;
+        parts.path_and_query = Some(path_and_query);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::QueryString;
+    use http::Uri;
+
+    #[test]
+    fn test_apply_to_uri_parts() {
+        let qs = QueryString::dynamic()
+            .with_value("q", "a&b#c")
+            .with_flag("debug");
+
+        let mut parts = Uri::from_static("http://example.com").into_parts();
+        qs.apply_to_uri_parts(&mut parts).unwrap();
+        let uri = Uri::from_parts(parts).unwrap();
+        assert_eq!(uri.path(), "/");
+        assert_eq!(uri.query(), Some("q=a%26b%23c&debug"));
+
+        let mut parts = uri.into_parts();
+        QueryString::dynamic()
+            .apply_to_uri_parts(&mut parts)
+            .unwrap();
+        let uri = Uri::from_parts(parts).unwrap();
+        assert_eq!(uri, "http://example.com/");
+        assert_eq!(uri.query(), None);
+    }
+
+    #[test]
+    fn test_apply_to_uri_parts_without_prefix() {
+        let qs = QueryString::cookie().with_value("q", "apple");
+
+        let mut parts = Uri::from_static("https://example.com/path").into_parts();
+        qs.apply_to_uri_parts(&mut parts).unwrap();
+        assert_eq!(parts.path_and_query.unwrap(), "/path?q=apple");
+    }
+
+    #[test]
+    fn test_apply_to_uri_parts_invalid() {
+        let qs = QueryString::dynamic()
+            .allow_literal(&[' '])
+            .with_value("q", "a b");
+
+        let mut parts = Uri::from_static("/path?x=1").into_parts();
+        assert!(qs.apply_to_uri_parts(&mut parts).is_err());
+        assert_eq!(parts.path_and_query.unwrap(), "/path?x=1");
+    }
+}
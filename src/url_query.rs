@@ -0,0 +1,80 @@
+use crate::QueryString;
+use url::Url;
+
+impl QueryString {
+    /// Creates a new query string builder from the decoded query pairs of the URL.
+    ///
+    /// The pairs are decoded like `application/x-www-form-urlencoded` data,
+    /// i.e. `+` is decoded as a space.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use query_string_builder::QueryString;
+    /// use url::Url;
+    ///
+    /// let mut url = Url::parse("https://example.com/search?q=apple+pie&page=1").unwrap();
+    ///
+    /// let mut qs = QueryString::from_url(&url);
+    /// assert_eq!(qs.get("q"), Some("apple pie"));
+    ///
+    /// qs.merge(QueryString::dynamic().with_value("page", 2));
+    /// qs.apply_to(&mut url);
+    ///
+    /// assert_eq!(url.as_str(), "https://example.com/search?q=apple%20pie&page=2");
+    /// ```
+    pub fn from_url(url: &Url) -> QueryString {
+        let mut qs = QueryString::dynamic();
+        for (key, value) in url.query_pairs() {
+            qs.push(key, value);
+        }
+        qs
+    }
+
+    /// Replaces the query of the URL with this query string.
+    /// An empty query string removes the query of the URL.
+    ///
+    /// See [`from_url`](Self::from_url) for an example.
+    pub fn apply_to(&self, url: &mut Url) {
+        let mut query = String::new();
+        self.write_encoded(&mut query, "")
+            .expect("writing to a String never fails");
+        url.set_query((!query.is_empty()).then_some(query.as_str()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::QueryString;
+    use url::Url;
+
+    #[test]
+    fn test_from_url() {
+        let url =
+            Url::parse("https://example.com/?a=1&b=%26%3D&flag&c=%F0%9F%8D%8E&a=2#top").unwrap();
+        let qs = QueryString::from_url(&url);
+        assert_eq!(qs.len(), 5);
+        assert_eq!(qs.get("b"), Some("&="));
+        assert_eq!(qs.get("c"), Some("🍎"));
+        assert_eq!(qs.to_string(), "?a=1&b=%26%3D&flag=&c=%F0%9F%8D%8E&a=2");
+
+        let url = Url::parse("https://example.com/").unwrap();
+        assert!(QueryString::from_url(&url).is_empty());
+    }
+
+    #[test]
+    fn test_apply_to() {
+        let mut url = Url::parse("https://example.com/path?old=1#top").unwrap();
+        QueryString::dynamic()
+            .with_value("q", "a b&c")
+            .with_flag("debug")
+            .apply_to(&mut url);
+        assert_eq!(
+            url.as_str(),
+            "https://example.com/path?q=a%20b%26c&debug#top"
+        );
+
+        QueryString::dynamic().apply_to(&mut url);
+        assert_eq!(url.as_str(), "https://example.com/path#top");
+    }
+}